@@ -0,0 +1,65 @@
+use std::env;
+
+use crate::Asset;
+
+/// Alignment in bytes of the embedded data, a power of two up to 4096. Data is not aligned when
+/// not set.
+const ALIGN_VAR: &str = "BEVY_EMBEDDED_ASSETS_ALIGN";
+/// Extensions of the assets to align, separated by `;`. All assets are aligned when not set.
+const ALIGN_EXTENSIONS_VAR: &str = "BEVY_EMBEDDED_ASSETS_ALIGN_EXTENSIONS";
+
+/// Largest supported alignment, the size of a memory page so that it holds in a mapped pack.
+const MAX_ALIGN: usize = 4096;
+
+pub fn rerun_if_env_changed() {
+    cargo_emit::rerun_if_env_changed!(ALIGN_VAR);
+    cargo_emit::rerun_if_env_changed!(ALIGN_EXTENSIONS_VAR);
+}
+
+/// Set the alignment of the assets matching the configured extensions.
+pub fn apply(assets: &mut [Asset]) {
+    let Ok(value) = env::var(ALIGN_VAR) else {
+        return;
+    };
+    let align = match value.trim().parse::<usize>() {
+        Ok(align) if align.is_power_of_two() && align <= MAX_ALIGN => align,
+        _ => panic!("${ALIGN_VAR} must be a power of two up to {MAX_ALIGN}, got {value}"),
+    };
+    let extensions = env::var(ALIGN_EXTENSIONS_VAR).unwrap_or_default();
+    let extensions = extensions
+        .split(';')
+        .map(|extension| extension.trim().trim_start_matches('.'))
+        .filter(|extension| !extension.is_empty())
+        .collect::<Vec<_>>();
+    for asset in assets {
+        let extension = asset
+            .path
+            .rsplit_once('.')
+            .map_or("", |(_, extension)| extension);
+        if extensions.is_empty() || extensions.contains(&extension) {
+            asset.align = align;
+        }
+    }
+}
+
+/// Declaration of a static with the data of an asset, aligned if needed.
+pub fn embed(index: usize, asset: &Asset) -> String {
+    let path = asset.full_path.to_string_lossy();
+    if asset.align <= 1 {
+        return format!(
+            "#[allow(clippy::non_ascii_literal)]\nstatic ASSET_{index}: &[u8] = include_bytes!({path:?});\n"
+        );
+    }
+    format!(
+        r#"#[allow(clippy::non_ascii_literal)]
+static ASSET_{index}: &[u8] = {{
+    #[repr(C, align({align}))]
+    struct Aligned<T>(T);
+    static DATA: Aligned<[u8; {len}]> = Aligned(*include_bytes!({path:?}));
+    &DATA.0
+}};
+"#,
+        align = asset.align,
+        len = asset.bytes.len(),
+    )
+}
@@ -0,0 +1,157 @@
+use std::{cmp::Reverse, env};
+
+use crate::{Asset, report::human_size};
+
+/// Maximum total size of all embedded assets.
+const MAX_TOTAL_SIZE_VAR: &str = "BEVY_EMBEDDED_ASSETS_MAX_TOTAL_SIZE";
+/// Maximum size of a single embedded asset.
+const MAX_FILE_SIZE_VAR: &str = "BEVY_EMBEDDED_ASSETS_MAX_FILE_SIZE";
+/// Maximum total size per directory, as a list of `directory=size` separated by `;`.
+const DIR_BUDGETS_VAR: &str = "BEVY_EMBEDDED_ASSETS_DIR_BUDGETS";
+/// What to do when a budget is exceeded: `error` (default) or `warn`.
+const BUDGET_ACTION_VAR: &str = "BEVY_EMBEDDED_ASSETS_BUDGET_ACTION";
+
+/// Number of files listed when a budget over several files is exceeded.
+const MAX_LISTED: usize = 10;
+
+pub fn rerun_if_env_changed() {
+    cargo_emit::rerun_if_env_changed!(MAX_TOTAL_SIZE_VAR);
+    cargo_emit::rerun_if_env_changed!(MAX_FILE_SIZE_VAR);
+    cargo_emit::rerun_if_env_changed!(DIR_BUDGETS_VAR);
+    cargo_emit::rerun_if_env_changed!(BUDGET_ACTION_VAR);
+}
+
+/// Check the embedded assets against the configured budgets, failing the build if one is exceeded.
+pub fn check(assets: &[&Asset]) {
+    let mut exceeded = false;
+
+    if let Some(max) = read_size(MAX_TOTAL_SIZE_VAR) {
+        let total: usize = assets
+            .iter()
+            .filter(|asset| asset.duplicate_of.is_none())
+            .map(|asset| asset.bytes.len())
+            .sum();
+        if total > max {
+            exceeded = true;
+            cargo_emit::warning!(
+                "Embedded assets total {}, over the budget of {} set by ${}",
+                human_size(total),
+                human_size(max),
+                MAX_TOTAL_SIZE_VAR
+            );
+            list_largest(assets.iter().copied());
+        }
+    }
+
+    if let Some(max) = read_size(MAX_FILE_SIZE_VAR) {
+        let mut offending = assets
+            .iter()
+            .filter(|asset| asset.bytes.len() > max)
+            .copied()
+            .collect::<Vec<_>>();
+        if !offending.is_empty() {
+            exceeded = true;
+            offending.sort_by_key(|asset| Reverse(asset.bytes.len()));
+            cargo_emit::warning!(
+                "{} assets are over the budget of {} per file set by ${}",
+                offending.len(),
+                human_size(max),
+                MAX_FILE_SIZE_VAR
+            );
+            for asset in offending {
+                cargo_emit::warning!(
+                    "  {:>10}  {}",
+                    human_size(asset.bytes.len()),
+                    asset.display_path()
+                );
+            }
+        }
+    }
+
+    for (dir, max) in read_dir_budgets() {
+        // embedded paths are separated by `/`, the directory may be written with `\` on Windows
+        let prefix = format!("{}/", dir.replace('\\', "/").trim_matches('/'));
+        let in_dir = assets
+            .iter()
+            .copied()
+            .filter(|asset| asset.duplicate_of.is_none() && asset.path.starts_with(&prefix));
+        let total: usize = in_dir.clone().map(|asset| asset.bytes.len()).sum();
+        if total > max {
+            exceeded = true;
+            cargo_emit::warning!(
+                "Embedded assets in {} total {}, over the budget of {} set by ${}",
+                dir,
+                human_size(total),
+                human_size(max),
+                DIR_BUDGETS_VAR
+            );
+            list_largest(in_dir);
+        }
+    }
+
+    if exceeded && !env::var(BUDGET_ACTION_VAR).is_ok_and(|action| action == "warn") {
+        panic!("Embedded assets are over budget");
+    }
+}
+
+fn list_largest<'a>(assets: impl Iterator<Item = &'a Asset>) {
+    let mut assets = assets.collect::<Vec<_>>();
+    assets.sort_by_key(|asset| Reverse(asset.bytes.len()));
+    for asset in assets.into_iter().take(MAX_LISTED) {
+        cargo_emit::warning!(
+            "  {:>10}  {}",
+            human_size(asset.bytes.len()),
+            asset.display_path()
+        );
+    }
+}
+
+fn read_size(var: &str) -> Option<usize> {
+    let value = env::var(var).ok()?;
+    let size = parse_size(&value);
+    if size.is_none() {
+        cargo_emit::warning!("${} is not a valid size: {}", var, value);
+    }
+    size
+}
+
+fn read_dir_budgets() -> Vec<(String, usize)> {
+    let Ok(value) = env::var(DIR_BUDGETS_VAR) else {
+        return vec![];
+    };
+    value
+        .split(';')
+        .filter(|budget| !budget.trim().is_empty())
+        .filter_map(|budget| {
+            let parsed = budget
+                .split_once('=')
+                .and_then(|(dir, size)| Some((dir.trim().to_string(), parse_size(size)?)));
+            if parsed.is_none() {
+                cargo_emit::warning!(
+                    "${} has an invalid budget, expected `directory=size`: {}",
+                    DIR_BUDGETS_VAR,
+                    budget
+                );
+            }
+            parsed
+        })
+        .collect()
+}
+
+/// Parse a size in bytes, with an optional unit suffix like `KB`, `MiB` or `G`.
+pub fn parse_size(value: &str) -> Option<usize> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: usize = number.parse().ok()?;
+    let multiplier = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1024,
+        "m" | "mb" | "mib" => 1024 * 1024,
+        "g" | "gb" | "gib" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    number.checked_mul(multiplier)
+}
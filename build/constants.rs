@@ -0,0 +1,127 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    fs,
+    path::Path,
+};
+
+use crate::Asset;
+
+/// Name of the generated file in `OUT_DIR`.
+pub const CONSTANTS_FILE: &str = "asset_paths.rs";
+
+/// Keywords that can't be used as identifiers, even as raw identifiers.
+const RESERVED: [&str; 5] = ["self", "Self", "super", "crate", "_"];
+
+/// A folder of the asset tree, with its files and subfolders.
+#[derive(Default)]
+struct Folder<'a> {
+    files: BTreeSet<&'a str>,
+    folders: BTreeMap<&'a str, Folder<'a>>,
+}
+
+/// Write a module with a constant for the path of each asset, mirroring the asset folder.
+///
+/// Does nothing if the `path-constants` feature is not enabled.
+pub fn write(out_dir: &Path, assets: &[&Asset]) {
+    if std::env::var("CARGO_FEATURE_PATH_CONSTANTS").is_err() {
+        return;
+    }
+
+    let mut root = Folder::default();
+    for asset in assets {
+        let mut folder = &mut root;
+        // asset paths are separated by `/` whatever the target, so folders are the same everywhere
+        let mut segments = asset.path.split('/').peekable();
+        while let Some(segment) = segments.next() {
+            if segments.peek().is_some() {
+                folder = folder.folders.entry(segment).or_default();
+            } else {
+                folder.files.insert(&asset.path);
+            }
+        }
+    }
+
+    let mut generated = String::new();
+    write_folder(&mut generated, &root, "");
+    fs::write(out_dir.join(CONSTANTS_FILE), generated).unwrap();
+}
+
+/// Write an empty module, for when there are no assets to generate constants for.
+pub fn write_empty(out_dir: &Path) {
+    fs::write(out_dir.join(CONSTANTS_FILE), "").unwrap();
+}
+
+fn write_folder(generated: &mut String, folder: &Folder, path: &str) {
+    let mut used = BTreeSet::new();
+    for file in &folder.files {
+        let name = file.rsplit('/').next().unwrap();
+        let ident = identifier(name, true);
+        if !used.insert(ident.clone()) {
+            cargo_emit::warning!(
+                "Skipping path constant for {}, its name {} is already used",
+                file,
+                ident
+            );
+            continue;
+        }
+        writeln!(generated, "/// Path to the `{file}` asset.").unwrap();
+        writeln!(generated, "pub const {ident}: &str = {file:?};").unwrap();
+    }
+    for (name, subfolder) in &folder.folders {
+        let ident = identifier(name, false);
+        if !used.insert(ident.clone()) {
+            cargo_emit::warning!(
+                "Skipping path constants for folder {}{}, its name {} is already used",
+                path,
+                name,
+                ident
+            );
+            continue;
+        }
+        let path = format!("{path}{name}/");
+        writeln!(generated, "/// Assets in the `{path}` folder.").unwrap();
+        writeln!(generated, "pub mod {ident} {{").unwrap();
+        write_folder(generated, subfolder, &path);
+        writeln!(generated, "}}").unwrap();
+    }
+}
+
+/// Convert a file or folder name to a Rust identifier, `SCREAMING_CASE` for constants and
+/// `snake_case` for modules.
+fn identifier(name: &str, constant: bool) -> String {
+    let mut ident = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            if constant {
+                ident.extend(c.to_uppercase());
+            } else {
+                ident.extend(c.to_lowercase());
+            }
+        } else if !ident.is_empty() && !ident.ends_with('_') {
+            ident.push('_');
+        }
+    }
+    let mut ident = ident.trim_end_matches('_').to_string();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_numeric()) {
+        ident.insert(0, '_');
+    }
+    if RESERVED.contains(&ident.as_str()) {
+        ident.push('_');
+    } else if is_keyword(&ident) {
+        ident.insert_str(0, "r#");
+    }
+    ident
+}
+
+/// Check if a name is a Rust keyword, which needs to be used as a raw identifier.
+fn is_keyword(name: &str) -> bool {
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+        "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in",
+        "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+        "return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe",
+        "unsized", "use", "virtual", "where", "while", "yield",
+    ];
+    KEYWORDS.contains(&name)
+}
@@ -0,0 +1,64 @@
+use std::{fmt::Write as _, fs, path::Path};
+
+use crate::Asset;
+
+/// Name of the generated file in `OUT_DIR`.
+const EMBEDDED_PATHS_FILE: &str = "embedded_paths.rs";
+
+/// Write the list of embedded paths used by the `embedded_path!` macro to check paths at compile
+/// time, and the errors suggesting the closest one when a path is not found.
+pub fn write(out_dir: &Path, assets: &[&Asset]) {
+    let mut generated = String::new();
+    writeln!(
+        generated,
+        "/// Paths of all embedded assets, separated by `/` whatever the target."
+    )
+    .unwrap();
+    writeln!(generated, "const EMBEDDED_PATHS: &[&str] = &[").unwrap();
+    for asset in assets {
+        writeln!(generated, "    {:?},", asset.path).unwrap();
+    }
+    writeln!(generated, "];").unwrap();
+
+    writeln!(
+        generated,
+        "/// Fail compilation with a suggestion for the closest embedded path."
+    )
+    .unwrap();
+    if assets.is_empty() {
+        writeln!(
+            generated,
+            "const fn unknown_embedded_path(_closest: usize) -> ! {{"
+        )
+        .unwrap();
+        writeln!(
+            generated,
+            "    panic!(\"this path is not an embedded asset\")"
+        )
+        .unwrap();
+        writeln!(generated, "}}").unwrap();
+    } else {
+        writeln!(
+            generated,
+            "const fn unknown_embedded_path(closest: usize) -> ! {{"
+        )
+        .unwrap();
+        writeln!(generated, "    match closest {{").unwrap();
+        for (i, asset) in assets.iter().enumerate() {
+            let message = format!(
+                "this path is not an embedded asset, did you mean `{}`?",
+                asset.path
+            );
+            writeln!(generated, "        {i} => panic!({message:?}),").unwrap();
+        }
+        writeln!(
+            generated,
+            "        _ => panic!(\"this path is not an embedded asset\"),"
+        )
+        .unwrap();
+        writeln!(generated, "    }}").unwrap();
+        writeln!(generated, "}}").unwrap();
+    }
+
+    fs::write(out_dir.join(EMBEDDED_PATHS_FILE), generated).unwrap();
+}
@@ -0,0 +1,47 @@
+use std::env;
+
+use crate::Asset;
+
+/// What to do with Git LFS pointer files: `error` (default) to fail the build, `warn`, or `off`.
+const LFS_VAR: &str = "BEVY_EMBEDDED_ASSETS_LFS_POINTERS";
+
+/// First line of a Git LFS pointer file.
+const LFS_POINTER_HEADER: &[u8] = b"version https://git-lfs.github.com/spec/v1";
+/// Git LFS pointer files are small text files, they are never larger than this.
+const LFS_POINTER_MAX_SIZE: usize = 1024;
+
+pub fn rerun_if_env_changed() {
+    cargo_emit::rerun_if_env_changed!(LFS_VAR);
+}
+
+/// Check that no asset is a Git LFS pointer, which happens when the repository was cloned without
+/// fetching the LFS files.
+pub fn check(assets: &[Asset]) {
+    let action = env::var(LFS_VAR).unwrap_or_default();
+    if action == "off" {
+        return;
+    }
+
+    let pointers = assets
+        .iter()
+        .filter(|asset| is_lfs_pointer(&asset.bytes))
+        .collect::<Vec<_>>();
+    if pointers.is_empty() {
+        return;
+    }
+
+    cargo_emit::warning!(
+        "{} assets are Git LFS pointers instead of the actual files, run `git lfs pull` to fetch them:",
+        pointers.len()
+    );
+    for asset in &pointers {
+        cargo_emit::warning!("  {}", asset.display_path());
+    }
+    if action != "warn" {
+        panic!("Embedded assets contain Git LFS pointers");
+    }
+}
+
+fn is_lfs_pointer(bytes: &[u8]) -> bool {
+    bytes.len() <= LFS_POINTER_MAX_SIZE && bytes.starts_with(LFS_POINTER_HEADER)
+}
@@ -0,0 +1,104 @@
+use std::{collections::BTreeMap, env};
+
+use crate::Asset;
+
+/// What to do with non-portable file names: `warn` (default), `error` to fail the build, or `off`.
+const LINT_VAR: &str = "BEVY_EMBEDDED_ASSETS_LINT";
+/// Maximum length of an asset path, relative to the asset folder.
+const MAX_PATH_LENGTH_VAR: &str = "BEVY_EMBEDDED_ASSETS_MAX_PATH_LENGTH";
+/// Maximum length of an asset path when not set, leaving room for the install folder under the
+/// 260 characters limit on Windows.
+const DEFAULT_MAX_PATH_LENGTH: usize = 200;
+
+/// Names reserved by Windows, with or without an extension.
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+/// Characters that can't be used in a file name on Windows.
+const INVALID_CHARACTERS: &[char] = &['<', '>', ':', '"', '|', '?', '*', '\\'];
+
+pub fn rerun_if_env_changed() {
+    cargo_emit::rerun_if_env_changed!(LINT_VAR);
+    cargo_emit::rerun_if_env_changed!(MAX_PATH_LENGTH_VAR);
+}
+
+/// Check that asset paths are portable: that they don't collide on case-insensitive file systems,
+/// are valid on Windows, and are not too long.
+pub fn check(assets: &[Asset]) {
+    let action = env::var(LINT_VAR).unwrap_or_default();
+    if action == "off" {
+        return;
+    }
+    let max_length = env::var(MAX_PATH_LENGTH_VAR)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_PATH_LENGTH);
+
+    let mut issues = 0;
+
+    let mut by_lowercase: BTreeMap<(bool, String), Vec<&Asset>> = BTreeMap::new();
+    for asset in assets {
+        by_lowercase
+            .entry((asset.processed, asset.path.to_lowercase()))
+            .or_default()
+            .push(asset);
+    }
+    for colliding in by_lowercase.values().filter(|assets| assets.len() > 1) {
+        issues += 1;
+        cargo_emit::warning!(
+            "Asset paths only differ by case, they will collide on case-insensitive file systems: {}",
+            colliding
+                .iter()
+                .map(|asset| asset.display_path())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    for asset in assets {
+        // paths are separated by `/` on every target, a `\` can only be in a file name
+        for segment in asset.path.split('/') {
+            if let Some(reason) = invalid_on_windows(segment) {
+                issues += 1;
+                cargo_emit::warning!(
+                    "Asset path is not valid on Windows, {}: {}",
+                    reason,
+                    asset.display_path()
+                );
+            }
+        }
+        let length = asset.path.chars().count();
+        if length > max_length {
+            issues += 1;
+            cargo_emit::warning!(
+                "Asset path is {} characters long, over the limit of {} set by ${}: {}",
+                length,
+                max_length,
+                MAX_PATH_LENGTH_VAR,
+                asset.display_path()
+            );
+        }
+    }
+
+    if issues > 0 && action == "error" {
+        panic!("{issues} asset paths are not portable");
+    }
+}
+
+/// Check if a file or folder name is valid on Windows, returning why it's not.
+fn invalid_on_windows(name: &str) -> Option<&'static str> {
+    let stem = name.split('.').next().unwrap_or_default().trim_end();
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+    {
+        Some("the name is reserved")
+    } else if name.contains(INVALID_CHARACTERS) || name.contains(char::is_control) {
+        Some("the name contains an invalid character")
+    } else if name.ends_with('.') || name.ends_with(' ') {
+        Some("the name ends with a dot or a space")
+    } else {
+        None
+    }
+}
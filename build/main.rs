@@ -0,0 +1,349 @@
+use std::{
+    cell::LazyCell,
+    collections::HashMap,
+    env,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+mod align;
+mod budget;
+mod constants;
+mod embedded_path;
+mod lfs;
+mod lint;
+mod pack;
+mod references;
+mod report;
+mod sidecar;
+mod unused;
+mod validate;
+
+use unicode_normalization::UnicodeNormalization;
+
+const ASSET_PATH_VAR: &str = "BEVY_ASSET_PATH";
+const PROCESSED_ASSET_PATH_VAR: &str = "BEVY_PROCESSED_ASSET_PATH";
+/// How processed assets should be embedded: `auto` (default) to embed the processed asset folder
+/// instead of the asset folder if it exists, `both` to embed both, the processed assets being used
+/// in `AssetMode::Processed`, or `false` to only embed the asset folder.
+const PROCESSED_VAR: &str = "BEVY_EMBEDDED_ASSETS_PROCESSED";
+/// Folder of the executables, where the files shipped with them are written. Needed when Cargo's
+/// `build.build-dir` is set, as it can't be found from `OUT_DIR` then.
+const EXECUTABLE_DIR_VAR: &str = "BEVY_EMBEDDED_ASSETS_EXECUTABLE_DIR";
+
+/// Name of the generated file in `OUT_DIR` with the embedded data and its index.
+const INDEX_FILE: &str = "embedded_assets.rs";
+
+/// A file found in the asset folder, that will be embedded.
+pub struct Asset {
+    /// Full path to the file on disk.
+    pub full_path: PathBuf,
+    /// Path of the file relative to the asset folder, as it will be available at runtime,
+    /// separated by `/`.
+    pub path: String,
+    /// Folder the file was found in.
+    pub source: PathBuf,
+    /// If this is a processed asset, or a raw one.
+    pub processed: bool,
+    /// Content of the file.
+    pub bytes: Vec<u8>,
+    /// Hash of the content of the file.
+    pub hash: blake3::Hash,
+    /// Index of an identical asset that was found before this one, whose data will be shared.
+    pub duplicate_of: Option<usize>,
+    /// If this asset is too large to be embedded, and is shipped in the sidecar folder instead.
+    pub sidecar: bool,
+    /// Alignment in bytes of the data of this asset.
+    pub align: usize,
+}
+
+impl Asset {
+    /// Path of the asset for messages, marking processed assets.
+    pub fn display_path(&self) -> String {
+        if self.processed {
+            format!("{} (processed)", self.path)
+        } else {
+            self.path.clone()
+        }
+    }
+}
+
+fn main() {
+    cargo_emit::rerun_if_env_changed!(ASSET_PATH_VAR);
+    cargo_emit::rerun_if_env_changed!(PROCESSED_ASSET_PATH_VAR);
+    cargo_emit::rerun_if_env_changed!(PROCESSED_VAR);
+    cargo_emit::rerun_if_env_changed!(EXECUTABLE_DIR_VAR);
+    align::rerun_if_env_changed();
+    report::rerun_if_env_changed();
+    budget::rerun_if_env_changed();
+    lint::rerun_if_env_changed();
+    lfs::rerun_if_env_changed();
+    validate::rerun_if_env_changed();
+    references::rerun_if_env_changed();
+    unused::rerun_if_env_changed();
+    sidecar::rerun_if_env_changed();
+    pack::rerun_if_env_changed();
+
+    let out_dir = env::var_os("OUT_DIR").unwrap();
+    let out_dir = Path::new(&out_dir);
+    // Only look for the target folder when the asset folders are not set
+    let project_dir = LazyCell::new(|| find_project_dir(out_dir));
+    let processed = env::var(PROCESSED_VAR).unwrap_or_default();
+    let both = processed == "both";
+
+    // Check if env variable is set for the assets folder
+    let configured_raw_dir = find_dir(ASSET_PATH_VAR);
+    let processed_dir = if processed == "false" || (!both && configured_raw_dir.is_some()) {
+        None
+    } else {
+        find_dir(PROCESSED_ASSET_PATH_VAR)
+            // Otherwise, look for an imported_assets folder next to the target folder
+            .or_else(|| {
+                project_dir
+                    .as_ref()
+                    .map(|dir| dir.join("imported_assets").join("Default"))
+            })
+            .filter(|path| path.exists())
+    };
+    if both && processed_dir.is_none() {
+        cargo_emit::warning!(
+            "Could not find processed asset folder, please specify its path with ${}",
+            PROCESSED_ASSET_PATH_VAR
+        );
+        panic!("No processed asset folder found");
+    }
+    let (raw_dir, processed_dir) = match processed_dir {
+        Some(dir) if !both => (Some(dir), None),
+        processed_dir => (
+            configured_raw_dir
+                // Otherwise, look for an assets folder next to the target folder
+                .or_else(|| project_dir.as_ref().map(|dir| dir.join("assets")))
+                .filter(|path| path.exists()),
+            processed_dir,
+        ),
+    };
+
+    if raw_dir.is_some() || processed_dir.is_some() {
+        let mut assets = vec![];
+        for (dir, processed) in [(&raw_dir, false), (&processed_dir, true)] {
+            let Some(dir) = dir else {
+                continue;
+            };
+            cargo_emit::rerun_if_changed!(dir.to_string_lossy());
+            if processed {
+                cargo_emit::warning!("Processed asset folder found: {}", dir.to_string_lossy());
+            } else {
+                cargo_emit::warning!("Asset folder found: {}", dir.to_string_lossy());
+            }
+            let mut found = collect_assets(dir, processed);
+            found.sort_by(|a, b| a.path.cmp(&b.path));
+            assets.append(&mut found);
+        }
+        unused::check(&mut assets);
+        sidecar::split(&mut assets);
+        align::apply(&mut assets);
+        deduplicate(&mut assets);
+
+        let pack_only = pack::only();
+        let dest_path = out_dir.join(INDEX_FILE);
+        let mut file = File::create(dest_path).unwrap();
+        for (i, asset) in assets.iter().enumerate() {
+            cargo_emit::rerun_if_changed!(asset.full_path.to_string_lossy());
+            if asset.duplicate_of.is_none() && !asset.sidecar && !pack_only {
+                file.write_all(align::embed(i, asset).as_ref()).unwrap();
+            }
+        }
+        write_index(&mut file, if pack_only { &[] } else { &assets });
+
+        let raw_assets = assets
+            .iter()
+            .filter(|asset| !asset.processed)
+            .collect::<Vec<_>>();
+        report::report(out_dir, &assets);
+        constants::write(out_dir, &raw_assets);
+        embedded_path::write(out_dir, &raw_assets);
+        lfs::check(&assets);
+        lint::check(&assets);
+        validate::check(&assets);
+        references::check(&raw_assets);
+        budget::check(
+            &assets
+                .iter()
+                .filter(|asset| !asset.sidecar)
+                .collect::<Vec<_>>(),
+        );
+        sidecar::write(out_dir, &assets);
+        pack::write(out_dir, &assets);
+    } else if env::var("DOCS_RS").is_ok()
+        || (env::var("CARGO_FEATURE_CLI").is_ok() && project_dir.is_none())
+    {
+        // the command line tool doesn't need assets, it can be installed with `cargo install`
+        // which builds outside of a `target` folder
+        let dest_path = out_dir.join(INDEX_FILE);
+
+        let mut file = File::create(dest_path).unwrap();
+        write_index(&mut file, &[]);
+        constants::write_empty(out_dir);
+        embedded_path::write(out_dir, &[]);
+        sidecar::write(out_dir, &[]);
+        pack::write(out_dir, &[]);
+    } else {
+        cargo_emit::warning!(
+            "Could not find asset folder, please specify its path with ${}",
+            ASSET_PATH_VAR
+        );
+        panic!("No asset folder found");
+    }
+}
+
+/// Read a folder from an environment variable, warning if it doesn't exist.
+fn find_dir(var: &str) -> Option<PathBuf> {
+    env::var(var)
+        .ok()
+        .map(|v| Path::new(&v).to_path_buf())
+        .and_then(|path| {
+            if path.exists() {
+                Some(path)
+            } else {
+                cargo_emit::warning!(
+                    "${} points to an unknown folder: {}",
+                    var,
+                    path.to_string_lossy()
+                );
+                None
+            }
+        })
+}
+
+/// Search for the target folder, and return the folder containing it.
+fn find_project_dir(out_dir: &Path) -> Option<PathBuf> {
+    for ancestor in out_dir.ancestors() {
+        if let Some(last) = ancestor.file_name()
+            && last == "target"
+        {
+            return ancestor.parent().map(Path::to_path_buf);
+        }
+    }
+    cargo_emit::warning!("Could not find asset folder from Cargo build directory");
+    None
+}
+
+/// Folder of the executables, set by `$BEVY_EMBEDDED_ASSETS_EXECUTABLE_DIR` or found from `OUT_DIR`.
+fn executable_dir(out_dir: &Path) -> PathBuf {
+    if let Ok(dir) = env::var(EXECUTABLE_DIR_VAR) {
+        return dir.into();
+    }
+    // OUT_DIR is `<build-dir>/<profile>/build/<package>/out`, executables are in
+    // `<target-dir>/<profile>`, the build dir being the target dir unless set otherwise
+    out_dir.ancestors().nth(3).unwrap_or(out_dir).to_path_buf()
+}
+
+fn collect_assets(dir: &Path, processed: bool) -> Vec<Asset> {
+    visit_dirs(dir)
+        .into_iter()
+        .map(|fullpath| {
+            // always separated by `/` whatever the host and the target, and normalized to NFC, as
+            // file systems like macOS' may store names decomposed
+            let path = fullpath
+                .strip_prefix(dir)
+                .unwrap()
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+                .nfc()
+                .collect::<String>();
+            let bytes = fs::read(&fullpath).unwrap();
+            Asset {
+                full_path: fullpath,
+                path,
+                source: dir.to_path_buf(),
+                processed,
+                hash: blake3::hash(&bytes),
+                bytes,
+                duplicate_of: None,
+                sidecar: false,
+                align: 1,
+            }
+        })
+        .collect()
+}
+
+/// Write the index of all embedded assets, raw ones then processed ones, each sorted by path.
+fn write_index(file: &mut File, assets: &[Asset]) {
+    file.write_all(
+        "/// Generated index of all embedded assets.
+#[allow(clippy::non_ascii_literal)]
+static EMBEDDED_ASSETS: &[EmbeddedAsset] = &[\n"
+            .as_ref(),
+    )
+    .unwrap();
+    for (i, asset) in assets.iter().enumerate() {
+        let (data, align) = if asset.sidecar {
+            ("None".to_string(), 1)
+        } else {
+            let first = asset.duplicate_of.unwrap_or(i);
+            (format!("Some(ASSET_{first})"), assets[first].align)
+        };
+        file.write_all(
+            format!(
+                r#"    EmbeddedAsset {{
+        path: {:?},
+        processed: {},
+        data: {},
+        size: {},
+        hash: {:?},
+        align: {},
+    }},
+"#,
+                asset.path,
+                asset.processed,
+                data,
+                asset.bytes.len(),
+                asset.hash.to_hex().as_str(),
+                align
+            )
+            .as_ref(),
+        )
+        .unwrap();
+    }
+    file.write_all("];\n".as_ref()).unwrap();
+}
+
+fn visit_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut collected = vec![];
+    if dir.is_dir() {
+        for entry in fs::read_dir(dir).unwrap() {
+            let entry = entry.unwrap();
+            let path = entry.path();
+            if path.is_dir() {
+                collected.append(&mut visit_dirs(&path));
+            } else {
+                collected.push(path);
+            }
+        }
+    }
+    collected
+}
+
+/// Mark assets whose content is identical to a previous one, so that it's only embedded once.
+fn deduplicate(assets: &mut [Asset]) {
+    let mut seen: HashMap<blake3::Hash, usize> = HashMap::new();
+    for i in 0..assets.len() {
+        if assets[i].sidecar {
+            continue;
+        }
+        match seen.get(&assets[i].hash) {
+            Some(&first) if assets[first].bytes == assets[i].bytes => {
+                assets[i].duplicate_of = Some(first);
+                // the shared data must satisfy the alignment of all its assets
+                assets[first].align = assets[first].align.max(assets[i].align);
+            }
+            Some(_) => (),
+            None => {
+                seen.insert(assets[i].hash, i);
+            }
+        }
+    }
+}
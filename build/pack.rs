@@ -0,0 +1,110 @@
+use std::{env, fs, path::Path};
+
+use crate::{Asset, executable_dir, report::human_size};
+
+/// Path of the pack file to write. Defaults to `assets.pack` next to the executable.
+const PACK_PATH_VAR: &str = "BEVY_EMBEDDED_ASSETS_PACK_PATH";
+/// Set to `true` to only write assets in the pack, and not embed them in the executable.
+const PACK_ONLY_VAR: &str = "BEVY_EMBEDDED_ASSETS_PACK_ONLY";
+/// Name of the pack file when not set.
+const DEFAULT_PACK_FILE: &str = "assets.pack";
+
+/// Magic bytes at the start of a pack file, must match `src/pack.rs`.
+const PACK_MAGIC: &[u8; 8] = b"BEVYPACK";
+/// Version of the pack format, must match `src/pack.rs`.
+const PACK_VERSION: u32 = 2;
+
+/// Name of the generated file in `OUT_DIR`.
+const PACK_FILE: &str = "pack.rs";
+
+pub fn rerun_if_env_changed() {
+    cargo_emit::rerun_if_env_changed!(PACK_PATH_VAR);
+    cargo_emit::rerun_if_env_changed!(PACK_ONLY_VAR);
+}
+
+/// If assets should only be available in the pack, and not embedded.
+pub fn only() -> bool {
+    enabled() && env::var(PACK_ONLY_VAR).is_ok_and(|v| v == "true")
+}
+
+fn enabled() -> bool {
+    env::var("CARGO_FEATURE_PACK").is_ok()
+}
+
+/// Write all assets in a pack file, and where it was written for the runtime to find it.
+///
+/// The pack starts with the magic bytes and the format version, followed by the number of assets
+/// and an index with, for each asset, if it's processed, its path, the offset and length of its
+/// data in the file, and its alignment. The data of all assets follows the index, identical assets sharing the
+/// same data, each starting at an offset that is a multiple of its alignment. All numbers are
+/// little endian.
+///
+/// Does nothing if the `pack` feature is not enabled.
+pub fn write(out_dir: &Path, assets: &[Asset]) {
+    if !enabled() {
+        return;
+    }
+    let build_dir = executable_dir(out_dir);
+    let path =
+        env::var(PACK_PATH_VAR).map_or_else(|_| build_dir.join(DEFAULT_PACK_FILE), Into::into);
+
+    let index_size: usize = assets
+        .iter()
+        .map(|asset| 1 + 4 + asset.path.len() + 8 + 8 + 4)
+        .sum();
+    let mut offset = (PACK_MAGIC.len() + 4 + 4 + index_size) as u64;
+    let mut offsets = Vec::with_capacity(assets.len());
+    for asset in assets {
+        if let Some(first) = asset.duplicate_of {
+            offsets.push(offsets[first]);
+        } else {
+            offset = offset.next_multiple_of(asset.align as u64);
+            offsets.push(offset);
+            offset += asset.bytes.len() as u64;
+        }
+    }
+
+    let mut pack = Vec::with_capacity(usize::try_from(offset).unwrap());
+    pack.extend_from_slice(PACK_MAGIC);
+    pack.extend_from_slice(&PACK_VERSION.to_le_bytes());
+    pack.extend_from_slice(&u32::try_from(assets.len()).unwrap().to_le_bytes());
+    for (asset, offset) in assets.iter().zip(&offsets) {
+        pack.push(u8::from(asset.processed));
+        pack.extend_from_slice(&u32::try_from(asset.path.len()).unwrap().to_le_bytes());
+        pack.extend_from_slice(asset.path.as_bytes());
+        pack.extend_from_slice(&offset.to_le_bytes());
+        pack.extend_from_slice(&(asset.bytes.len() as u64).to_le_bytes());
+        pack.extend_from_slice(&u32::try_from(asset.align).unwrap().to_le_bytes());
+    }
+    for (asset, offset) in assets.iter().zip(&offsets) {
+        if asset.duplicate_of.is_none() {
+            pack.resize(usize::try_from(*offset).unwrap(), 0);
+            pack.extend_from_slice(&asset.bytes);
+        }
+    }
+
+    if fs::read(&path).ok().as_ref() != Some(&pack) {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(&path, &pack).unwrap();
+    }
+    cargo_emit::warning!(
+        "Packed {} assets for a total of {} in {}",
+        assets.len(),
+        human_size(pack.len()),
+        path.to_string_lossy()
+    );
+
+    fs::write(
+        out_dir.join(PACK_FILE),
+        format!(
+            "/// Folder the pack was written to during the build, only known to debug builds to not ship\n\
+             /// the path of the build machine.\n\
+             #[cfg(debug_assertions)]\n\
+             const PACK_BUILD_DIR: &str = {:?};\n",
+            build_dir.to_string_lossy()
+        ),
+    )
+    .unwrap();
+}
@@ -0,0 +1,196 @@
+use std::{collections::HashSet, env};
+
+use serde_json::Value;
+
+use crate::Asset;
+
+/// What to do with references to assets that are not embedded: `warn`, or `error` to fail the
+/// build. References are not checked when not set.
+const REFERENCES_VAR: &str = "BEVY_EMBEDDED_ASSETS_CHECK_REFERENCES";
+/// Patterns of asset paths to look for in strings of `.ron` files, separated by `;`. A `*` matches
+/// any sequence of characters, like `*.png`.
+const REFERENCE_PATTERNS_VAR: &str = "BEVY_EMBEDDED_ASSETS_REFERENCE_PATTERNS";
+
+pub fn rerun_if_env_changed() {
+    cargo_emit::rerun_if_env_changed!(REFERENCES_VAR);
+    cargo_emit::rerun_if_env_changed!(REFERENCE_PATTERNS_VAR);
+}
+
+/// Check that assets referenced by glTF files, and by `.ron` files if patterns are configured,
+/// are embedded.
+pub fn check(assets: &[&Asset]) {
+    let Ok(action) = env::var(REFERENCES_VAR) else {
+        return;
+    };
+    let patterns = env::var(REFERENCE_PATTERNS_VAR).unwrap_or_default();
+    let patterns = patterns
+        .split(';')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .collect::<Vec<_>>();
+
+    let embedded = assets
+        .iter()
+        .map(|asset| asset.path.as_str())
+        .collect::<HashSet<_>>();
+
+    let mut dangling = 0;
+    for asset in assets {
+        let references = if asset.path.ends_with(".gltf") {
+            serde_json::from_slice(&asset.bytes)
+                .map(|gltf| gltf_references(&asset.path, &gltf))
+                .unwrap_or_default()
+        } else if asset.path.ends_with(".glb") {
+            glb_json(&asset.bytes)
+                .and_then(|json| serde_json::from_slice(json).ok())
+                .map(|gltf| gltf_references(&asset.path, &gltf))
+                .unwrap_or_default()
+        } else if asset.path.ends_with(".ron") && !patterns.is_empty() {
+            ron_references(&asset.bytes, &patterns)
+        } else {
+            vec![]
+        };
+        for reference in references {
+            if !embedded.contains(reference.as_str()) {
+                dangling += 1;
+                cargo_emit::warning!(
+                    "{} references {}, which is not embedded",
+                    asset.path,
+                    reference
+                );
+            }
+        }
+    }
+
+    if dangling > 0 && action == "error" {
+        panic!("{dangling} references to assets that are not embedded");
+    }
+}
+
+/// Paths of the buffers and images of a glTF file, relative to the asset folder.
+fn gltf_references(path: &str, gltf: &Value) -> Vec<String> {
+    let directory = path.rsplit_once('/').map_or("", |(directory, _)| directory);
+    ["buffers", "images"]
+        .iter()
+        .filter_map(|key| gltf.get(key)?.as_array())
+        .flatten()
+        .filter_map(|item| item.get("uri")?.as_str())
+        .filter(|uri| !uri.starts_with("data:"))
+        .map(|uri| resolve(directory, &percent_decode(uri)))
+        .collect()
+}
+
+/// Get the JSON chunk of a binary glTF file.
+fn glb_json(bytes: &[u8]) -> Option<&[u8]> {
+    const JSON_CHUNK: &[u8] = b"JSON";
+    if bytes.get(0..4)? != b"glTF" {
+        return None;
+    }
+    let length = u32::from_le_bytes(bytes.get(12..16)?.try_into().ok()?) as usize;
+    if bytes.get(16..20)? != JSON_CHUNK {
+        return None;
+    }
+    bytes.get(20..20 + length)
+}
+
+/// Asset paths found in the strings of a `.ron` file matching one of the patterns.
+fn ron_references(bytes: &[u8], patterns: &[&str]) -> Vec<String> {
+    let Ok(text) = std::str::from_utf8(bytes) else {
+        return vec![];
+    };
+    string_literals(text)
+        .into_iter()
+        // Ignore labels, and paths in other asset sources
+        .map(|literal| {
+            literal
+                .split_once('#')
+                .map_or(literal.as_str(), |(path, _)| path)
+                .to_string()
+        })
+        .filter(|path| !path.contains("://"))
+        .filter(|path| patterns.iter().any(|pattern| matches(pattern, path)))
+        .map(|path| resolve("", &path))
+        .collect()
+}
+
+/// Extract the content of string literals from RON.
+fn string_literals(text: &str) -> Vec<String> {
+    let mut literals = vec![];
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let mut literal = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => literal.extend(chars.next()),
+                        c => literal.push(c),
+                    }
+                }
+                literals.push(literal);
+            }
+            // skip comments, they may contain quotes
+            '/' if chars.as_str().starts_with('/') => {
+                chars.by_ref().find(|c| *c == '\n');
+            }
+            _ => (),
+        }
+    }
+    literals
+}
+
+/// Check if a path matches a pattern, where `*` matches any sequence of characters.
+fn matches(pattern: &str, path: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == path,
+        Some((prefix, rest)) => {
+            let Some(path) = path.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=path.len())
+                .filter(|i| path.is_char_boundary(*i))
+                .any(|i| matches(rest, &path[i..]))
+        }
+    }
+}
+
+/// Resolve a relative path from a directory, removing `.` and `..` segments, separated by `/` like
+/// the embedded paths. References written with `\` are accepted.
+fn resolve(directory: &str, path: &str) -> String {
+    let mut segments = directory
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    for segment in path.split(['/', '\\']) {
+        match segment {
+            "" | "." => (),
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// Decode `%XX` escapes of a URI.
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = uri
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,128 @@
+use std::{env, fs, path::Path};
+
+use serde_json::json;
+
+use crate::{Asset, pack};
+
+/// Path where a copy of the report should be written, in addition to the one in `OUT_DIR`.
+const REPORT_PATH_VAR: &str = "BEVY_EMBEDDED_ASSETS_REPORT";
+/// Number of the largest assets to list in the summary. `0` disables the summary.
+const REPORT_TOP_VAR: &str = "BEVY_EMBEDDED_ASSETS_REPORT_TOP";
+/// Number of the largest assets to list in the summary when not set.
+const DEFAULT_TOP: usize = 5;
+/// Name of the report file in `OUT_DIR`.
+const REPORT_FILE: &str = "embedded_assets_report.json";
+
+pub fn rerun_if_env_changed() {
+    cargo_emit::rerun_if_env_changed!(REPORT_PATH_VAR);
+    cargo_emit::rerun_if_env_changed!(REPORT_TOP_VAR);
+}
+
+/// Write a JSON report of the embedded assets, and print a summary of the largest ones.
+///
+/// Assets in the sidecar folder or only in the pack are in the report, but not in the summary as
+/// they don't add to the size of the executable.
+///
+/// Compressing every asset is slow on large asset folders, so the compressed size is only computed
+/// when a copy of the report is requested, and never for assets in the sidecar folder.
+pub fn report(out_dir: &Path, assets: &[Asset]) {
+    let requested = env::var(REPORT_PATH_VAR).ok();
+    let entries = assets
+        .iter()
+        .map(|asset| {
+            let compressed_size = (requested.is_some() && !asset.sidecar)
+                .then(|| miniz_oxide::deflate::compress_to_vec(&asset.bytes, 6).len());
+            json!({
+                "path": asset.path,
+                "size": asset.bytes.len(),
+                "compressed_size": compressed_size,
+                "hash": asset.hash.to_hex().as_str(),
+                "source": asset.source.to_string_lossy(),
+                "processed": asset.processed,
+                "duplicate_of": asset.duplicate_of.map(|i| &assets[i].path),
+                "sidecar": asset.sidecar,
+            })
+        })
+        .collect::<Vec<_>>();
+    let total_size: usize = assets.iter().map(|asset| asset.bytes.len()).sum();
+    let deduplicated_size: usize = assets
+        .iter()
+        .filter(|asset| asset.duplicate_of.is_some())
+        .map(|asset| asset.bytes.len())
+        .sum();
+    let sidecar_size: usize = assets
+        .iter()
+        .filter(|asset| asset.sidecar)
+        .map(|asset| asset.bytes.len())
+        .sum();
+    let pack_only = pack::only();
+    let embedded_size = if pack_only {
+        0
+    } else {
+        total_size - deduplicated_size - sidecar_size
+    };
+    let report = json!({
+        "total_size": total_size,
+        "embedded_size": embedded_size,
+        "deduplicated_size": deduplicated_size,
+        "sidecar_size": sidecar_size,
+        "count": assets.len(),
+        "assets": entries,
+    });
+    let report = serde_json::to_string_pretty(&report).unwrap();
+
+    fs::write(out_dir.join(REPORT_FILE), &report).unwrap();
+    if let Some(path) = requested
+        && let Err(err) = fs::write(&path, &report)
+    {
+        cargo_emit::warning!("Could not write asset report to {}: {}", path, err);
+    }
+
+    let top = env::var(REPORT_TOP_VAR)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_TOP);
+    if top == 0 || pack_only {
+        return;
+    }
+    let embedded = assets
+        .iter()
+        .filter(|asset| !asset.sidecar)
+        .collect::<Vec<_>>();
+    cargo_emit::warning!(
+        "Embedded {} assets for a total of {}",
+        embedded.len(),
+        human_size(embedded_size)
+    );
+    if deduplicated_size > 0 {
+        cargo_emit::warning!(
+            "Saved {} by embedding identical assets only once",
+            human_size(deduplicated_size)
+        );
+    }
+    let mut largest = embedded;
+    largest.sort_by(|a, b| b.bytes.len().cmp(&a.bytes.len()).then(a.path.cmp(&b.path)));
+    for asset in largest.into_iter().take(top) {
+        cargo_emit::warning!(
+            "  {:>10}  {}",
+            human_size(asset.bytes.len()),
+            asset.display_path()
+        );
+    }
+}
+
+/// Format a size in bytes for humans.
+#[allow(clippy::cast_precision_loss)]
+pub fn human_size(size: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if size < 1024 {
+        return format!("{size} B");
+    }
+    let mut size = size as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
@@ -0,0 +1,86 @@
+use std::{env, fmt::Write as _, fs, path::Path};
+
+use crate::{Asset, budget::parse_size, executable_dir, report::human_size};
+
+/// Assets larger than this size are not embedded, but copied to the sidecar folder next to the
+/// executable. All assets are embedded when not set.
+const MAX_EMBEDDED_SIZE_VAR: &str = "BEVY_EMBEDDED_ASSETS_MAX_EMBEDDED_SIZE";
+/// Name of the sidecar folder, next to the executable.
+const SIDECAR_DIR_VAR: &str = "BEVY_EMBEDDED_ASSETS_SIDECAR_DIR";
+/// Name of the sidecar folder when not set.
+const DEFAULT_SIDECAR_DIR: &str = "sidecar_assets";
+
+/// Name of the generated file in `OUT_DIR`.
+const SIDECAR_FILE: &str = "sidecar.rs";
+
+pub fn rerun_if_env_changed() {
+    cargo_emit::rerun_if_env_changed!(MAX_EMBEDDED_SIZE_VAR);
+    cargo_emit::rerun_if_env_changed!(SIDECAR_DIR_VAR);
+}
+
+/// Mark the assets over the size limit to be shipped in the sidecar folder instead of embedded.
+///
+/// Meta files are always embedded.
+pub fn split(assets: &mut [Asset]) {
+    let Ok(value) = env::var(MAX_EMBEDDED_SIZE_VAR) else {
+        return;
+    };
+    let Some(max) = parse_size(&value) else {
+        cargo_emit::warning!("${} is not a valid size: {}", MAX_EMBEDDED_SIZE_VAR, value);
+        return;
+    };
+    for asset in assets {
+        asset.sidecar = asset.bytes.len() > max && !asset.path.ends_with(".meta");
+    }
+}
+
+/// Copy the sidecar assets next to the executable, and write where to find them at runtime.
+pub fn write(out_dir: &Path, assets: &[Asset]) {
+    let name = env::var(SIDECAR_DIR_VAR).unwrap_or_else(|_| DEFAULT_SIDECAR_DIR.to_string());
+    let dir = executable_dir(out_dir).join(&name);
+
+    let sidecar = assets
+        .iter()
+        .filter(|asset| asset.sidecar)
+        .collect::<Vec<_>>();
+    for asset in &sidecar {
+        let destination = dir
+            .join(if asset.processed { "processed" } else { "raw" })
+            .join(&asset.path);
+        if fs::read(&destination).is_ok_and(|existing| existing == asset.bytes) {
+            continue;
+        }
+        fs::create_dir_all(destination.parent().unwrap()).unwrap();
+        fs::write(&destination, &asset.bytes).unwrap();
+    }
+    if !sidecar.is_empty() {
+        cargo_emit::warning!(
+            "{} assets for a total of {} are not embedded but copied to {}",
+            sidecar.len(),
+            human_size(sidecar.iter().map(|asset| asset.bytes.len()).sum()),
+            dir.to_string_lossy()
+        );
+    }
+
+    let mut generated = String::new();
+    writeln!(
+        generated,
+        "/// Name of the sidecar folder, next to the executable."
+    )
+    .unwrap();
+    writeln!(generated, "const SIDECAR_DIR: &str = {name:?};").unwrap();
+    writeln!(
+        generated,
+        "/// Sidecar folder the assets were copied to during the build, only known to debug builds to\n\
+         /// not ship the path of the build machine.\n\
+         #[cfg(debug_assertions)]"
+    )
+    .unwrap();
+    writeln!(
+        generated,
+        "const SIDECAR_BUILD_DIR: &str = {:?};",
+        dir.to_string_lossy()
+    )
+    .unwrap();
+    fs::write(out_dir.join(SIDECAR_FILE), generated).unwrap();
+}
@@ -0,0 +1,64 @@
+use std::{collections::HashSet, env, fs};
+
+use crate::{Asset, report::human_size};
+
+/// Files written by the `record-access` feature with the paths of the assets read at runtime,
+/// separated by `;`. Unused assets are not reported when not set.
+const ACCESS_LOG_VAR: &str = "BEVY_EMBEDDED_ASSETS_ACCESS_LOG";
+/// Set to `true` to not embed the assets that are not in the access logs.
+const EXCLUDE_UNUSED_VAR: &str = "BEVY_EMBEDDED_ASSETS_EXCLUDE_UNUSED";
+
+pub fn rerun_if_env_changed() {
+    cargo_emit::rerun_if_env_changed!(ACCESS_LOG_VAR);
+    cargo_emit::rerun_if_env_changed!(EXCLUDE_UNUSED_VAR);
+}
+
+/// Report the assets that were never read according to the access logs, and remove them if
+/// asked to.
+pub fn check(assets: &mut Vec<Asset>) {
+    let Ok(logs) = env::var(ACCESS_LOG_VAR) else {
+        return;
+    };
+    let mut used = HashSet::new();
+    for log in logs.split(';').map(str::trim).filter(|log| !log.is_empty()) {
+        cargo_emit::rerun_if_changed!(log);
+        let content = fs::read_to_string(log)
+            .unwrap_or_else(|err| panic!("Could not read access log {log}: {err}"));
+        used.extend(
+            content
+                .lines()
+                .map(|line| line.trim().replace('\\', "/"))
+                .filter(|line| !line.is_empty()),
+        );
+    }
+
+    // both the logged paths and the asset paths are separated by `/`, whatever the target
+    let is_used = |asset: &Asset| {
+        used.contains(&asset.path)
+            || asset
+                .path
+                .strip_suffix(".meta")
+                .is_some_and(|path| used.contains(path))
+    };
+    let unused = assets
+        .iter()
+        .filter(|asset| !is_used(asset))
+        .collect::<Vec<_>>();
+    if unused.is_empty() {
+        return;
+    }
+
+    cargo_emit::warning!(
+        "{} assets for a total of {} were never read according to the access logs:",
+        unused.len(),
+        human_size(unused.iter().map(|asset| asset.bytes.len()).sum())
+    );
+    for asset in &unused {
+        cargo_emit::warning!("  {}", asset.display_path());
+    }
+
+    if env::var(EXCLUDE_UNUSED_VAR).is_ok_and(|v| v == "true") {
+        cargo_emit::warning!("Unused assets are not embedded");
+        assets.retain(is_used);
+    }
+}
@@ -0,0 +1,101 @@
+use std::env;
+
+use serde::{Deserialize, de::IgnoredAny};
+
+use crate::Asset;
+
+/// Which files to validate, as a list separated by `,` of `meta`, `ron` and `json`.
+const VALIDATE_VAR: &str = "BEVY_EMBEDDED_ASSETS_VALIDATE";
+
+/// Version of the meta format supported by Bevy.
+const META_FORMAT_VERSION: &str = "1.0";
+
+/// The envelope of Bevy `.meta` files, ignoring the settings that depend on the loader.
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct AssetMeta {
+    meta_format_version: String,
+    processed_info: Option<IgnoredAny>,
+    asset: AssetAction,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+enum AssetAction {
+    Load {
+        loader: String,
+        settings: IgnoredAny,
+    },
+    Process {
+        processor: String,
+        settings: IgnoredAny,
+    },
+    Ignore,
+}
+
+pub fn rerun_if_env_changed() {
+    cargo_emit::rerun_if_env_changed!(VALIDATE_VAR);
+}
+
+/// Validate the syntax of `.meta`, `.ron` and `.json` assets, failing the build if one is
+/// malformed.
+pub fn check(assets: &[Asset]) {
+    let Ok(validate) = env::var(VALIDATE_VAR) else {
+        return;
+    };
+    let validate = validate.split(',').map(str::trim).collect::<Vec<_>>();
+
+    let mut invalid = 0;
+    for asset in assets {
+        let error = if asset.path.ends_with(".meta") && validate.contains(&"meta") {
+            validate_meta(&asset.bytes)
+        } else if asset.path.ends_with(".ron") && validate.contains(&"ron") {
+            ron::de::from_bytes::<IgnoredAny>(&asset.bytes)
+                .err()
+                .map(|error| spanned_error(&error))
+        } else if asset.path.ends_with(".json") && validate.contains(&"json") {
+            serde_json::from_slice::<IgnoredAny>(&asset.bytes)
+                .err()
+                .map(|error| (error.line(), error.column(), error.to_string()))
+        } else {
+            None
+        };
+        if let Some((line, column, message)) = error {
+            invalid += 1;
+            cargo_emit::warning!(
+                "{}:{}:{}: {}",
+                asset.full_path.to_string_lossy(),
+                line,
+                column,
+                message
+            );
+        }
+    }
+
+    if invalid > 0 {
+        panic!("{invalid} embedded assets are malformed");
+    }
+}
+
+fn validate_meta(bytes: &[u8]) -> Option<(usize, usize, String)> {
+    match ron::de::from_bytes::<AssetMeta>(bytes) {
+        Err(error) => Some(spanned_error(&error)),
+        Ok(meta) if meta.meta_format_version != META_FORMAT_VERSION => Some((
+            1,
+            1,
+            format!(
+                "unsupported meta format version {}, expected {}",
+                meta.meta_format_version, META_FORMAT_VERSION
+            ),
+        )),
+        Ok(_) => None,
+    }
+}
+
+fn spanned_error(error: &ron::error::SpannedError) -> (usize, usize, String) {
+    (
+        error.span.start.line,
+        error.span.start.col,
+        error.code.to_string(),
+    )
+}
@@ -1,20 +1,31 @@
 use std::{
+    borrow::Cow,
     collections::HashMap,
-    io::Read,
-    path::{Path, PathBuf},
+    fs,
+    io::{self, Read},
+    path::{Component, Path, PathBuf},
     pin::Pin,
+    sync::{Arc, LazyLock},
     task::Poll,
 };
 
 use bevy_asset::io::{
-    AssetReader, AssetReaderError, ErasedAssetReader, PathStream, Reader, ReaderNotSeekableError,
-    SeekableReader,
+    AssetReader, AssetReaderError, AssetSource, ErasedAssetReader, PathStream, Reader,
+    ReaderNotSeekableError, SeekableReader,
 };
 use futures_io::{AsyncRead, AsyncSeek};
-use futures_lite::Stream;
+use futures_lite::{Stream, future::block_on};
 use thiserror::Error;
+use unicode_normalization::{UnicodeNormalization, is_nfc};
 
-use crate::{EmbeddedRegistry, include_all_assets};
+#[cfg(feature = "record-access")]
+use crate::AccessRecorder;
+#[cfg(feature = "diagnostics")]
+use crate::EmbeddedAssetStats;
+use crate::{
+    AssetCache, EmbeddedRegistry, embedded, include_all_assets, include_all_processed_assets,
+    sidecar,
+};
 
 /// Struct which can be used to retrieve embedded assets directly
 /// without the normal Bevy `Handle<T>` approach.  This is useful
@@ -37,8 +48,24 @@ use crate::{EmbeddedRegistry, include_all_assets};
 /// ```
 #[allow(clippy::module_name_repetitions)]
 pub struct EmbeddedAssetReader {
-    loaded: HashMap<&'static Path, &'static [u8]>,
+    /// Embedded assets, without data for the ones in the sidecar folder.
+    loaded: HashMap<&'static Path, Option<&'static [u8]>>,
+    /// Size of the assets in the sidecar folder, to stream the ones too large to be cached.
+    sidecar_sizes: HashMap<&'static Path, usize>,
+    /// Embedded paths by their lowercase version, to find them ignoring case.
+    #[cfg(feature = "case-insensitive")]
+    lowercase: HashMap<String, &'static Path>,
     fallback: Option<Box<dyn ErasedAssetReader>>,
+    /// Reader for the assets too large to be embedded.
+    sidecar: Option<Box<dyn ErasedAssetReader>>,
+    /// If the assets in the sidecar folder are processed ones.
+    processed: bool,
+    /// Cache for the assets read from the sidecar folder.
+    cache: Option<AssetCache>,
+    #[cfg(feature = "record-access")]
+    recorder: Option<AccessRecorder>,
+    #[cfg(feature = "diagnostics")]
+    stats: Option<EmbeddedAssetStats>,
 }
 
 impl std::fmt::Debug for EmbeddedAssetReader {
@@ -58,6 +85,10 @@ impl EmbeddedRegistry for &mut EmbeddedAssetReader {
     fn insert_included_asset(&mut self, name: &'static str, bytes: &'static [u8]) {
         self.add_asset(Path::new(name), bytes);
     }
+
+    fn insert_sidecar_asset(&mut self, name: &'static str, size: usize) {
+        self.insert_sidecar(Path::new(name), size);
+    }
 }
 
 impl EmbeddedAssetReader {
@@ -66,7 +97,17 @@ impl EmbeddedAssetReader {
     pub(crate) fn new() -> Self {
         Self {
             loaded: HashMap::default(),
+            sidecar_sizes: HashMap::default(),
+            #[cfg(feature = "case-insensitive")]
+            lowercase: HashMap::default(),
             fallback: None,
+            sidecar: None,
+            processed: false,
+            cache: None,
+            #[cfg(feature = "record-access")]
+            recorder: None,
+            #[cfg(feature = "diagnostics")]
+            stats: None,
         }
     }
 
@@ -77,12 +118,25 @@ impl EmbeddedAssetReader {
     /// calling `preloaded` with [`EmbeddedAssetReader::load_path_sync()`].
     #[must_use]
     pub fn preloaded() -> Self {
-        let mut new = Self {
-            loaded: HashMap::default(),
-            fallback: None,
-        };
+        let mut new = Self::new();
         include_all_assets(&mut new);
-        new
+        new.with_sidecar(false)
+    }
+
+    /// Create an [`EmbeddedAssetReader`] loaded with all the processed assets found by the build
+    /// script in the `imported_assets/Default` folder.
+    ///
+    /// When processed assets were not embedded separately, for example because the processed asset
+    /// folder was embedded instead of the asset folder, it has the same assets as
+    /// [`EmbeddedAssetReader::preloaded`].
+    #[must_use]
+    pub fn preloaded_processed() -> Self {
+        if embedded::processed_assets().is_empty() {
+            return Self::preloaded();
+        }
+        let mut new = Self::new();
+        include_all_processed_assets(&mut new);
+        new.with_sidecar(true)
     }
 
     /// Create an [`EmbeddedAssetReader`] loaded with all the assets found by the build script.
@@ -90,48 +144,239 @@ impl EmbeddedAssetReader {
     pub(crate) fn preloaded_with_default(
         mut default: impl FnMut() -> Box<dyn ErasedAssetReader> + Send + Sync + 'static,
     ) -> Self {
-        let mut new = Self {
-            loaded: HashMap::default(),
-            fallback: Some(default()),
-        };
+        let mut new = Self::new();
+        new.fallback = Some(default());
         include_all_assets(&mut new);
-        new
+        new.with_sidecar(false)
+    }
+
+    /// Create an [`EmbeddedAssetReader`] loaded with all the processed assets found by the build
+    /// script.
+    #[must_use]
+    pub(crate) fn preloaded_processed_with_default(
+        mut default: impl FnMut() -> Box<dyn ErasedAssetReader> + Send + Sync + 'static,
+    ) -> Self {
+        if embedded::processed_assets().is_empty() {
+            return Self::preloaded_with_default(default);
+        }
+        let mut new = Self::new();
+        new.fallback = Some(default());
+        include_all_processed_assets(&mut new);
+        new.with_sidecar(true)
+    }
+
+    /// Read the assets too large to be embedded from the sidecar folder, if there are some.
+    fn with_sidecar(mut self, processed: bool) -> Self {
+        self.processed = processed;
+        if self.loaded.values().any(Option::is_none) {
+            let dir = sidecar::source_dir(processed);
+            self.sidecar = Some(AssetSource::get_default_reader(
+                dir.to_string_lossy().into_owned(),
+            )());
+        }
+        self
+    }
+
+    /// Keep the assets read from the sidecar folder in a cache, shared with the other readers
+    /// using the same [`AssetCache`].
+    #[must_use]
+    pub fn with_cache(mut self, cache: AssetCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Read an asset from the sidecar folder, through the cache if there is one. Assets larger
+    /// than the budget of the cache are streamed from the sidecar folder.
+    async fn read_sidecar<'a>(
+        &'a self,
+        sidecar: &'a dyn ErasedAssetReader,
+        path: &'a Path,
+    ) -> Result<Box<dyn Reader + 'a>, AssetReaderError> {
+        let Some(cache) = self.cache.as_ref().filter(|cache| {
+            self.sidecar_sizes
+                .get(path)
+                .is_some_and(|size| cache.fits(*size))
+        }) else {
+            return sidecar.read(path).await;
+        };
+        if let Some(data) = cache.get(self.processed, path) {
+            return Ok(Box::new(CachedReader { data, position: 0 }));
+        }
+        let mut data = vec![];
+        sidecar.read(path).await?.read_to_end(&mut data).await?;
+        let data: Arc<[u8]> = Arc::from(data);
+        cache.insert(self.processed, path, data.clone());
+        Ok(Box::new(CachedReader { data, position: 0 }))
+    }
+
+    /// Record the paths of the embedded assets read by this [`EmbeddedAssetReader`].
+    ///
+    /// This is only available when the `record-access` cargo feature is enabled.
+    #[cfg(feature = "record-access")]
+    #[must_use]
+    pub fn with_recorder(mut self, recorder: AccessRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    #[cfg(feature = "record-access")]
+    fn record_access(&self, path: &Path) {
+        if let Some(recorder) = self.recorder.as_ref()
+            && let Some(embedded) = self.resolve(path)
+        {
+            recorder.record(embedded);
+        }
+    }
+
+    /// Count the assets read by this [`EmbeddedAssetReader`], embedded or from the fallback.
+    ///
+    /// This is only available when the `diagnostics` cargo feature is enabled.
+    #[cfg(feature = "diagnostics")]
+    #[must_use]
+    pub fn with_stats(mut self, stats: EmbeddedAssetStats) -> Self {
+        self.stats = Some(stats);
+        self
     }
 
     /// Add an asset to this [`EmbeddedAssetReader`].
     pub(crate) fn add_asset(&mut self, path: &'static Path, data: &'static [u8]) {
+        self.insert(path, Some(data));
+    }
+
+    /// Add an asset that is in the sidecar folder to this [`EmbeddedAssetReader`].
+    fn insert_sidecar(&mut self, path: &'static Path, size: usize) {
+        self.insert(path, None);
+        self.sidecar_sizes.insert(path, size);
+    }
+
+    fn insert(&mut self, path: &'static Path, data: Option<&'static [u8]>) {
+        #[cfg(feature = "case-insensitive")]
+        self.lowercase
+            .insert(lowercase(&normalize_path(path)), path);
         self.loaded.insert(path, data);
     }
 
     /// Get the data from the asset matching the path provided.
     ///
+    /// The path is normalized before looking for the asset, so that `./asset.png`,
+    /// `directory/../asset.png` or `.\asset.png` all match `asset.png`. Accented characters match
+    /// whether they are composed or decomposed. With the
+    /// `case-insensitive` feature, `Asset.png` also matches `asset.png`.
+    ///
     /// # Errors
     ///
-    /// This will returns an error if the path is not known.
+    /// This will returns an error if the path is not known, or if the asset was too large to be
+    /// embedded and is in the sidecar folder.
     pub fn load_path_sync(&self, path: &Path) -> Result<DataReader, AssetReaderError> {
-        self.loaded
-            .get(path)
-            .map(|b| DataReader(b))
+        self.resolve(path)
+            .and_then(|path| self.loaded[path])
+            .map(DataReader)
             .ok_or_else(|| AssetReaderError::NotFound(path.to_path_buf()))
     }
 
+    /// Write the embedded assets accepted by `filter` to a folder, keeping the structure of the
+    /// asset folder. Assets in the sidecar folder are copied too.
+    ///
+    /// This doesn't need an `App`, it can be used to give modders a copy of the assets, or a
+    /// path on disk to libraries that need one.
+    ///
+    /// ```rust,no_run
+    /// use bevy_embedded_assets::{EmbeddedAssetReader, ExtractMode};
+    ///
+    /// let written = EmbeddedAssetReader::preloaded()
+    ///     .extract(
+    ///         "mods/template",
+    ///         |path| path.starts_with("levels"),
+    ///         ExtractMode::MissingOrDifferent,
+    ///     )
+    ///     .unwrap();
+    /// ```
+    ///
+    /// Returns the paths of the files written.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if a file can't be written, or an asset can't be read from the
+    /// sidecar folder.
+    pub fn extract(
+        &self,
+        dir: impl AsRef<Path>,
+        filter: impl Fn(&Path) -> bool,
+        mode: ExtractMode,
+    ) -> io::Result<Vec<PathBuf>> {
+        let mut paths = self
+            .loaded
+            .keys()
+            .copied()
+            .filter(|path| filter(path))
+            // don't write outside of the folder for paths like `../file`
+            .filter(|path| {
+                path.components()
+                    .all(|component| matches!(component, Component::Normal(_)))
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        let mut written = vec![];
+        for path in paths {
+            let data = match (self.loaded[path], self.sidecar.as_ref()) {
+                (Some(data), _) => Cow::Borrowed(data),
+                (None, Some(sidecar)) => Cow::Owned(
+                    block_on(async {
+                        let mut data = vec![];
+                        sidecar.read(path).await?.read_to_end(&mut data).await?;
+                        Ok::<_, AssetReaderError>(data)
+                    })
+                    .map_err(io::Error::other)?,
+                ),
+                (None, None) => continue,
+            };
+            let destination = dir.as_ref().join(path);
+            if mode == ExtractMode::MissingOrDifferent
+                && fs::read(&destination).is_ok_and(|existing| existing == *data)
+            {
+                continue;
+            }
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&destination, &data)?;
+            written.push(destination);
+        }
+        Ok(written)
+    }
+
+    /// Find the embedded path matching the path provided.
+    fn resolve(&self, path: &Path) -> Option<&'static Path> {
+        let path = normalize_path(path);
+        if let Some((embedded, _)) = self.loaded.get_key_value(path.as_path()) {
+            return Some(embedded);
+        }
+        #[cfg(feature = "case-insensitive")]
+        if let Some(embedded) = self.lowercase.get(&lowercase(&path)) {
+            return Some(embedded);
+        }
+        None
+    }
+
     fn has_file_sync(&self, path: &Path) -> bool {
-        self.loaded.contains_key(path)
+        self.resolve(path).is_some()
     }
 
     fn is_directory_sync(&self, path: &Path) -> bool {
-        let as_folder = path.join("");
+        let path = normalize_path(path);
         self.loaded
             .keys()
-            .any(|loaded_path| loaded_path.starts_with(&as_folder) && loaded_path != &path)
+            .any(|loaded_path| is_in_directory(loaded_path, &path))
     }
 
     fn read_directory_sync(&self, path: &Path) -> Result<DirReader, AssetReaderError> {
         if self.is_directory_sync(path) {
+            let path = normalize_path(path);
             let paths: Vec<_> = self
                 .loaded
                 .keys()
-                .filter(|loaded_path| loaded_path.starts_with(path))
+                .filter(|loaded_path| is_in_directory(loaded_path, &path))
                 .map(|t| t.to_path_buf())
                 .collect();
             Ok(DirReader(paths))
@@ -141,10 +386,87 @@ impl EmbeddedAssetReader {
     }
 }
 
+/// Embedded raw assets, to find them from any path matching them.
+static RAW_ASSETS: LazyLock<EmbeddedAssetReader> = LazyLock::new(|| {
+    let mut reader = EmbeddedAssetReader::new();
+    include_all_assets(&mut reader);
+    reader
+});
+
+/// Embedded processed assets, to find them from any path matching them.
+static PROCESSED_ASSETS: LazyLock<EmbeddedAssetReader> = LazyLock::new(|| {
+    let mut reader = EmbeddedAssetReader::new();
+    include_all_processed_assets(&mut reader);
+    reader
+});
+
+/// Get the data of an embedded asset, resolving its path like an [`EmbeddedAssetReader`] does.
+pub(crate) fn embedded_data(path: &Path, processed: bool) -> Option<&'static [u8]> {
+    let reader = if processed && !embedded::processed_assets().is_empty() {
+        &PROCESSED_ASSETS
+    } else {
+        &RAW_ASSETS
+    };
+    reader.loaded[reader.resolve(path)?]
+}
+
+/// Normalize a path to how embedded paths are stored: `/` as separator, without `.` segments,
+/// with `..` segments resolved, and in Unicode Normalization Form C.
+pub(crate) fn normalize_path(path: &Path) -> PathBuf {
+    let mut path = path.to_string_lossy();
+    if !is_nfc(&path) {
+        path = Cow::Owned(path.nfc().collect());
+    }
+    let mut segments: Vec<&str> = vec![];
+    for segment in path.split(['/', '\\']) {
+        match segment {
+            "" | "." => (),
+            ".." if segments.last().is_some_and(|last| *last != "..") => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    PathBuf::from(segments.join("/"))
+}
+
+#[cfg(feature = "case-insensitive")]
+fn lowercase(path: &Path) -> String {
+    path.to_string_lossy().to_lowercase()
+}
+
+/// Check if an embedded path is inside a directory, at any depth.
+fn is_in_directory(path: &Path, directory: &Path) -> bool {
+    #[cfg(feature = "case-insensitive")]
+    {
+        let directory = lowercase(directory);
+        let path = lowercase(&normalize_path(path));
+        directory.is_empty()
+            || path
+                .strip_prefix(&directory)
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+    #[cfg(not(feature = "case-insensitive"))]
+    {
+        path.starts_with(directory) && path != directory
+    }
+}
+
+/// When to write an embedded asset to disk with [`EmbeddedAssetReader::extract`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExtractMode {
+    /// Always write the file, replacing it if it exists.
+    #[default]
+    Overwrite,
+    /// Only write the file if it's missing, or if its content is different.
+    MissingOrDifferent,
+}
+
 /// A wrapper around the raw bytes of an asset.
 /// This is returned by [`EmbeddedAssetReader::load_path_sync()`].
 ///
-/// To get the raw data, use `reader.0`.
+/// To get the raw data, use `reader.0`. It is aligned as reported by
+/// [`EmbeddedAsset::align`](crate::embedded::EmbeddedAsset::align) for the same asset.
 #[derive(Default, Debug, Clone, Copy)]
 pub struct DataReader(pub &'static [u8]);
 
@@ -152,11 +474,8 @@ impl Reader for DataReader {
     fn read_to_end<'a>(
         &'a mut self,
         buf: &'a mut Vec<u8>,
-    ) -> bevy_asset::io::StackFuture<
-        'a,
-        std::io::Result<usize>,
-        { bevy_asset::io::STACK_FUTURE_SIZE },
-    > {
+    ) -> bevy_asset::io::StackFuture<'a, io::Result<usize>, { bevy_asset::io::STACK_FUTURE_SIZE }>
+    {
         let future = futures_lite::AsyncReadExt::read_to_end(self, buf);
         bevy_asset::io::StackFuture::from(future)
     }
@@ -189,6 +508,71 @@ impl AsyncSeek for DataReader {
     }
 }
 
+/// A [`Reader`] over the data of an asset kept in an [`AssetCache`], without copying it.
+struct CachedReader {
+    data: Arc<[u8]>,
+    position: usize,
+}
+
+impl Reader for CachedReader {
+    fn read_to_end<'a>(
+        &'a mut self,
+        buf: &'a mut Vec<u8>,
+    ) -> bevy_asset::io::StackFuture<'a, io::Result<usize>, { bevy_asset::io::STACK_FUTURE_SIZE }>
+    {
+        let future = futures_lite::AsyncReadExt::read_to_end(self, buf);
+        bevy_asset::io::StackFuture::from(future)
+    }
+
+    fn seekable(&mut self) -> Result<&mut dyn SeekableReader, ReaderNotSeekableError> {
+        Ok(self)
+    }
+}
+
+impl AsyncRead for CachedReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<futures_io::Result<usize>> {
+        let this = self.get_mut();
+        let read = (&this.data[this.position.min(this.data.len())..]).read(buf);
+        if let Ok(read) = read {
+            this.position += read;
+        }
+        Poll::Ready(read)
+    }
+}
+
+impl AsyncSeek for CachedReader {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+        pos: futures_io::SeekFrom,
+    ) -> Poll<futures_io::Result<u64>> {
+        let this = self.get_mut();
+        let position = match pos {
+            futures_io::SeekFrom::Start(offset) => usize::try_from(offset).ok(),
+            futures_io::SeekFrom::End(offset) => offset_position(this.data.len(), offset),
+            futures_io::SeekFrom::Current(offset) => offset_position(this.position, offset),
+        };
+        Poll::Ready(match position {
+            Some(position) => {
+                this.position = position;
+                Ok(position as u64)
+            }
+            None => Err(futures_io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        })
+    }
+}
+
+fn offset_position(base: usize, offset: i64) -> Option<usize> {
+    base.checked_add_signed(isize::try_from(offset).ok()?)
+}
+
 #[derive(Error, Debug)]
 enum EmbeddedDataReaderError {
     #[error("Seek is not supported when embedded")]
@@ -209,13 +593,15 @@ impl Stream for DirReader {
     }
 }
 
+/// Get the path of the meta file for an asset, `name.ext.meta`, or `name.meta` if the asset has
+/// no extension.
 pub(crate) fn get_meta_path(path: &Path) -> PathBuf {
     let mut meta_path = path.to_path_buf();
-    let mut extension = path
-        .extension()
-        .expect("asset paths must have extensions")
-        .to_os_string();
-    extension.push(".meta");
+    let mut extension = path.extension().unwrap_or_default().to_os_string();
+    if !extension.is_empty() {
+        extension.push(".");
+    }
+    extension.push("meta");
     meta_path.set_extension(extension);
     meta_path
 }
@@ -223,14 +609,42 @@ pub(crate) fn get_meta_path(path: &Path) -> PathBuf {
 impl AssetReader for EmbeddedAssetReader {
     // async fn read<'a>(&'a self, path: &'a Path) -> Result<Box<dyn Reader>, AssetReaderError> {
     async fn read<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
-        if self.has_file_sync(path) {
-            self.load_path_sync(path).map(|reader| {
-                let boxed: Box<dyn Reader> = Box::new(reader);
-                boxed
-            })
+        if let Some(embedded) = self.resolve(path) {
+            #[cfg(feature = "record-access")]
+            self.record_access(path);
+            let reader = match (self.loaded[embedded], self.sidecar.as_ref()) {
+                (Some(data), _) => {
+                    let boxed: Box<dyn Reader> = Box::new(DataReader(data));
+                    boxed
+                }
+                (None, Some(sidecar)) => self.read_sidecar(sidecar.as_ref(), embedded).await?,
+                (None, None) => {
+                    #[cfg(feature = "diagnostics")]
+                    if let Some(stats) = self.stats.as_ref() {
+                        stats.miss();
+                    }
+                    return Err(AssetReaderError::NotFound(path.to_path_buf()));
+                }
+            };
+            #[cfg(feature = "diagnostics")]
+            let reader = match self.stats.as_ref() {
+                Some(stats) => stats.embedded(reader),
+                None => reader,
+            };
+            Ok(reader)
         } else if let Some(fallback) = self.fallback.as_ref() {
-            fallback.read(path).await
+            let result = fallback.read(path).await;
+            #[cfg(feature = "diagnostics")]
+            let result = match self.stats.as_ref() {
+                Some(stats) => stats.fallback(result),
+                None => result,
+            };
+            result
         } else {
+            #[cfg(feature = "diagnostics")]
+            if let Some(stats) = self.stats.as_ref() {
+                stats.miss();
+            }
             Err(AssetReaderError::NotFound(path.to_path_buf()))
         }
     }
@@ -238,12 +652,23 @@ impl AssetReader for EmbeddedAssetReader {
     async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
         let meta_path = get_meta_path(path);
         if self.has_file_sync(&meta_path) {
+            #[cfg(feature = "record-access")]
+            self.record_access(&meta_path);
             self.load_path_sync(&meta_path).map(|reader| {
                 let boxed: Box<dyn Reader> = Box::new(reader);
                 boxed
             })
+        } else if self.has_file_sync(path) {
+            // An embedded asset only uses an embedded meta, the one from the fallback may not match
+            Err(AssetReaderError::NotFound(meta_path))
         } else if let Some(fallback) = self.fallback.as_ref() {
-            fallback.read_meta(path).await
+            let result = fallback.read_meta(path).await;
+            #[cfg(feature = "diagnostics")]
+            let result = match self.stats.as_ref() {
+                Some(stats) => stats.fallback_meta(result),
+                None => result,
+            };
+            result
         } else {
             Err(AssetReaderError::NotFound(meta_path))
         }
@@ -268,7 +693,21 @@ impl AssetReader for EmbeddedAssetReader {
 mod tests {
     use std::path::Path;
 
-    use crate::asset_reader::EmbeddedAssetReader;
+    use bevy_asset::io::{
+        AssetReader, AssetReaderError, Reader,
+        memory::{Dir, MemoryAssetReader},
+    };
+    use futures_io::SeekFrom;
+    use futures_lite::future::block_on;
+
+    use crate::{
+        AssetCache,
+        asset_reader::{EmbeddedAssetReader, ExtractMode, get_meta_path},
+    };
+    #[cfg(feature = "diagnostics")]
+    use crate::{EmbeddedAssetStats, ReadStats};
+    #[cfg(feature = "record-access")]
+    use {crate::AccessRecorder, std::path::PathBuf};
 
     #[cfg_attr(not(target_arch = "wasm32"), test)]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
@@ -329,6 +768,311 @@ mod tests {
         assert_eq!(list, vec!["directory/asset.png", "directory/asset2.png"]);
     }
 
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn load_path_normalized() {
+        let mut embedded = EmbeddedAssetReader::new();
+        embedded.add_asset(Path::new("asset.png"), &[1, 2, 3]);
+        embedded.add_asset(Path::new("directory/asset.png"), &[4, 5, 6]);
+        for path in ["./asset.png", "directory/../asset.png", "/asset.png"] {
+            assert_eq!(
+                embedded.load_path_sync(Path::new(path)).unwrap().0,
+                [1, 2, 3]
+            );
+        }
+        for path in [
+            "directory//asset.png",
+            "./directory/./asset.png",
+            "directory\\asset.png",
+        ] {
+            assert_eq!(
+                embedded.load_path_sync(Path::new(path)).unwrap().0,
+                [4, 5, 6]
+            );
+        }
+        assert!(embedded.load_path_sync(Path::new("../asset.png")).is_err());
+
+        embedded.add_asset(Path::new("açèt.png"), &[7, 8, 9]);
+        let decomposed = "ac\u{327}e\u{300}t.png";
+        assert_eq!(
+            embedded.load_path_sync(Path::new(decomposed)).unwrap().0,
+            [7, 8, 9]
+        );
+        assert!(embedded.is_directory_sync(Path::new("./directory")));
+        assert!(
+            embedded
+                .read_directory_sync(Path::new("./directory/"))
+                .is_ok()
+        );
+    }
+
+    #[cfg(feature = "case-insensitive")]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn load_path_case_insensitive() {
+        let mut embedded = EmbeddedAssetReader::new();
+        embedded.add_asset(Path::new("Directory/Asset.png"), &[1, 2, 3]);
+        assert_eq!(
+            embedded
+                .load_path_sync(Path::new("directory/asset.PNG"))
+                .unwrap()
+                .0,
+            [1, 2, 3]
+        );
+        assert!(embedded.is_directory_sync(Path::new("DIRECTORY")));
+        assert!(!embedded.is_directory_sync(Path::new("director")));
+    }
+
+    #[cfg(feature = "record-access")]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn record_access() {
+        let recorder = AccessRecorder::default();
+        let mut embedded = EmbeddedAssetReader::new().with_recorder(recorder.clone());
+        embedded.add_asset(Path::new("asset.png"), &[1]);
+        embedded.add_asset(Path::new("asset.png.meta"), &[2]);
+        embedded.add_asset(Path::new("unused.png"), &[3]);
+
+        block_on(async {
+            embedded.read(Path::new("./asset.png")).await.unwrap();
+            embedded.read_meta(Path::new("asset.png")).await.unwrap();
+            assert!(embedded.read(Path::new("missing.png")).await.is_err());
+        });
+        assert_eq!(
+            recorder.paths(),
+            [PathBuf::from("asset.png"), PathBuf::from("asset.png.meta")]
+        );
+    }
+
+    #[cfg(feature = "diagnostics")]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn count_reads() {
+        let dir = Dir::default();
+        dir.insert_asset(Path::new("on_disk.png"), vec![4, 5]);
+        dir.insert_meta(Path::new("on_disk.png"), vec![6]);
+        let stats = EmbeddedAssetStats::default();
+        let mut embedded = EmbeddedAssetReader::new().with_stats(stats.clone());
+        embedded.add_asset(Path::new("asset.png"), &[1, 2, 3]);
+        // in the sidecar folder, but there is no sidecar reader
+        embedded.insert_sidecar(Path::new("large.ogg"), 3);
+        embedded.fallback = Some(Box::new(MemoryAssetReader { root: dir }));
+
+        block_on(async {
+            let mut bytes = vec![];
+            let mut reader = embedded.read(Path::new("asset.png")).await.unwrap();
+            reader.read_to_end(&mut bytes).await.unwrap();
+            let mut reader = embedded.read(Path::new("on_disk.png")).await.unwrap();
+            reader.read_to_end(&mut bytes).await.unwrap();
+            let mut reader = embedded.read_meta(Path::new("on_disk.png")).await.unwrap();
+            reader.read_to_end(&mut bytes).await.unwrap();
+            assert!(embedded.read_meta(Path::new("missing.png")).await.is_err());
+            assert!(embedded.read(Path::new("missing.png")).await.is_err());
+            assert!(embedded.read(Path::new("large.ogg")).await.is_err());
+        });
+        assert_eq!(
+            stats.snapshot(),
+            ReadStats {
+                reads: 4,
+                misses: 3,
+                fallback_hits: 1,
+                fallback_errors: 1,
+                fallback_meta_hits: 1,
+                fallback_meta_errors: 1,
+                embedded_bytes: 3,
+                fallback_bytes: 3,
+            }
+        );
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn read_sidecar() {
+        let dir = Dir::default();
+        dir.insert_asset(Path::new("large.ogg"), vec![4, 5, 6]);
+        let mut embedded = EmbeddedAssetReader::new();
+        embedded.add_asset(Path::new("small.ron"), &[1, 2, 3]);
+        embedded.insert_sidecar(Path::new("large.ogg"), 3);
+        embedded.sidecar = Some(Box::new(MemoryAssetReader { root: dir }));
+
+        let read = |path: &str| {
+            let mut bytes = vec![];
+            block_on(async {
+                let mut reader = embedded.read(Path::new(path)).await?;
+                reader.read_to_end(&mut bytes).await?;
+                Ok::<_, AssetReaderError>(())
+            })
+            .map(|()| bytes)
+        };
+        assert_eq!(read("small.ron").unwrap(), [1, 2, 3]);
+        assert_eq!(read("./large.ogg").unwrap(), [4, 5, 6]);
+        assert!(read("missing.ogg").is_err());
+        assert!(embedded.load_path_sync(Path::new("large.ogg")).is_err());
+        assert!(embedded.is_directory_sync(Path::new("")));
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn read_sidecar_through_cache() {
+        let dir = Dir::default();
+        dir.insert_asset(Path::new("large.ogg"), vec![4, 5, 6]);
+        let cache = AssetCache::new(1024);
+        let mut embedded = EmbeddedAssetReader::new().with_cache(cache.clone());
+        embedded.add_asset(Path::new("small.ron"), &[1, 2, 3]);
+        embedded.insert_sidecar(Path::new("large.ogg"), 3);
+        embedded.sidecar = Some(Box::new(MemoryAssetReader { root: dir.clone() }));
+
+        let read = |path: &str| {
+            let mut bytes = vec![];
+            block_on(async {
+                let mut reader = embedded.read(Path::new(path)).await?;
+                reader.read_to_end(&mut bytes).await?;
+                Ok::<_, AssetReaderError>(())
+            })
+            .map(|()| bytes)
+        };
+        assert_eq!(read("small.ron").unwrap(), [1, 2, 3]);
+        assert_eq!(read("large.ogg").unwrap(), [4, 5, 6]);
+        dir.remove_asset(Path::new("large.ogg"));
+        assert_eq!(read("large.ogg").unwrap(), [4, 5, 6]);
+        block_on(async {
+            let mut reader = embedded.read(Path::new("large.ogg")).await.unwrap();
+            let seekable = reader.seekable().unwrap();
+            futures_lite::AsyncSeekExt::seek(seekable, SeekFrom::Start(1))
+                .await
+                .unwrap();
+            let mut bytes = vec![];
+            futures_lite::AsyncReadExt::read_to_end(seekable, &mut bytes)
+                .await
+                .unwrap();
+            assert_eq!(bytes, [5, 6]);
+        });
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+        assert_eq!((stats.entries, stats.size), (1, 3));
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn stream_sidecar_over_cache_budget() {
+        let dir = Dir::default();
+        dir.insert_asset(Path::new("large.ogg"), vec![4, 5, 6]);
+        let cache = AssetCache::new(2);
+        let mut embedded = EmbeddedAssetReader::new().with_cache(cache.clone());
+        embedded.insert_sidecar(Path::new("large.ogg"), 3);
+        embedded.sidecar = Some(Box::new(MemoryAssetReader { root: dir.clone() }));
+
+        let read = |path: &str| {
+            let mut bytes = vec![];
+            block_on(async {
+                let mut reader = embedded.read(Path::new(path)).await?;
+                reader.read_to_end(&mut bytes).await?;
+                Ok::<_, AssetReaderError>(())
+            })
+            .map(|()| bytes)
+        };
+        assert_eq!(read("large.ogg").unwrap(), [4, 5, 6]);
+        dir.remove_asset(Path::new("large.ogg"));
+        assert!(read("large.ogg").is_err());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (0, 0));
+        assert_eq!((stats.entries, stats.size), (0, 0));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn extract() {
+        let sidecar = Dir::default();
+        sidecar.insert_asset(Path::new("levels/large.ogg"), vec![7, 8]);
+        let mut embedded = EmbeddedAssetReader::new();
+        embedded.add_asset(Path::new("levels/one.ron"), &[1]);
+        embedded.add_asset(Path::new("levels/two.ron"), &[2]);
+        embedded.add_asset(Path::new("icon.png"), &[3]);
+        embedded.add_asset(Path::new("../outside.png"), &[4]);
+        embedded.insert_sidecar(Path::new("levels/large.ogg"), 2);
+        embedded.sidecar = Some(Box::new(MemoryAssetReader { root: sidecar }));
+
+        let dir = std::env::temp_dir().join("bevy_embedded_assets_extract");
+        let _ = std::fs::remove_dir_all(&dir);
+        let written = embedded
+            .extract(
+                &dir,
+                |path| path.starts_with("levels"),
+                ExtractMode::Overwrite,
+            )
+            .unwrap();
+        assert_eq!(
+            written,
+            [
+                dir.join("levels/large.ogg"),
+                dir.join("levels/one.ron"),
+                dir.join("levels/two.ron")
+            ]
+        );
+        assert_eq!(std::fs::read(dir.join("levels/large.ogg")).unwrap(), [7, 8]);
+        assert!(!dir.join("icon.png").exists());
+
+        std::fs::write(dir.join("levels/one.ron"), [9]).unwrap();
+        let written = embedded
+            .extract(&dir, |_| true, ExtractMode::MissingOrDifferent)
+            .unwrap();
+        assert_eq!(written, [dir.join("icon.png"), dir.join("levels/one.ron")]);
+        assert_eq!(std::fs::read(dir.join("levels/one.ron")).unwrap(), [1]);
+        assert!(!dir.parent().unwrap().join("outside.png").exists());
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn meta_path() {
+        assert_eq!(
+            get_meta_path(Path::new("asset.png")),
+            Path::new("asset.png.meta")
+        );
+        assert_eq!(
+            get_meta_path(Path::new("LICENSE")),
+            Path::new("LICENSE.meta")
+        );
+        assert_eq!(
+            get_meta_path(Path::new(".config")),
+            Path::new(".config.meta")
+        );
+        assert_eq!(
+            get_meta_path(Path::new("directory/.config")),
+            Path::new("directory/.config.meta")
+        );
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn read_meta_without_extension() {
+        let mut embedded = EmbeddedAssetReader::new();
+        embedded.add_asset(Path::new("LICENSE"), &[1]);
+        embedded.add_asset(Path::new("LICENSE.meta"), &[2]);
+        embedded.add_asset(Path::new("Makefile"), &[3]);
+        embedded.add_asset(Path::new("directory/.config"), &[4]);
+        embedded.add_asset(Path::new("directory/.config.meta"), &[5]);
+
+        let read_meta = |path: &str| {
+            let mut bytes = vec![];
+            block_on(async {
+                let mut reader = embedded.read_meta(Path::new(path)).await?;
+                reader.read_to_end(&mut bytes).await?;
+                Ok::<_, AssetReaderError>(())
+            })
+            .map(|()| bytes)
+        };
+        assert_eq!(read_meta("LICENSE").unwrap(), [2]);
+        assert!(read_meta("Makefile").is_err());
+        assert_eq!(read_meta("directory/.config").unwrap(), [5]);
+        assert!(
+            embedded
+                .load_path_sync(Path::new("directory/.config"))
+                .is_ok()
+        );
+    }
+
     #[cfg(target_arch = "wasm32")]
     use wasm_bindgen_test::wasm_bindgen_test;
 
@@ -379,4 +1123,19 @@ mod tests {
             "in subdirectory"
         );
     }
+
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn check_preloaded_duplicate() {
+        let embedded = EmbeddedAssetReader::preloaded();
+
+        let original = embedded
+            .load_path_sync(Path::new("example_asset.test"))
+            .unwrap();
+        let duplicate = embedded
+            .load_path_sync(Path::new("subdir/duplicate_asset.test"))
+            .unwrap();
+        assert_eq!(duplicate.0, b"hello");
+        assert!(std::ptr::eq(original.0, duplicate.0));
+    }
 }
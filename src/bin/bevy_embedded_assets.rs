@@ -0,0 +1,212 @@
+//! Command line tool to inspect the asset packs written by the build script, or executables with
+//! a pack appended.
+//!
+//! This is only available when the `cli` cargo feature is enabled.
+
+#![warn(
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unused_import_braces,
+    unused_qualifications,
+    missing_docs,
+    clippy::pedantic
+)]
+
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Component, Path, PathBuf},
+    process::ExitCode,
+};
+
+use bevy_embedded_assets::{AssetPack, PackedAsset};
+use unicode_normalization::UnicodeNormalization;
+
+const USAGE: &str = "Usage: bevy_embedded_assets <command>
+
+Commands work on an asset pack, or an executable with a pack appended.
+
+    list <pack>                                  List assets with their size and hash
+    extract <pack> <dir>                         Extract assets to <dir>/assets and <dir>/imported_assets/Default
+    verify <pack> <asset dir> [processed dir]    Check that assets match the files in a folder
+    diff <old pack> <new pack>                   List assets added, removed or changed between two packs
+
+`verify` and `diff` exit with code 1 if there are differences.";
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let result = match args.as_slice() {
+        ["list", pack] => list(pack),
+        ["extract", pack, dir] => extract(pack, dir),
+        ["verify", pack, dir] => verify(pack, dir, None),
+        ["verify", pack, dir, processed_dir] => verify(pack, dir, Some(processed_dir)),
+        ["diff", old, new] => diff(old, new),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Open a pack, or the pack appended to an executable.
+fn open(path: &str) -> io::Result<AssetPack> {
+    let path = std::path::absolute(path)?;
+    AssetPack::open_appended(&path).or_else(|_| AssetPack::open(&path))
+}
+
+fn display_path(asset: &PackedAsset) -> String {
+    if asset.processed {
+        format!("{} (processed)", asset.path)
+    } else {
+        asset.path.to_string()
+    }
+}
+
+fn hash(data: &[u8]) -> String {
+    blake3::hash(data).to_hex().to_string()
+}
+
+fn list(pack: &str) -> io::Result<bool> {
+    let pack = open(pack)?;
+    for asset in pack.assets() {
+        println!(
+            "{:>10}  {}  {}",
+            asset.data.len(),
+            hash(asset.data),
+            display_path(asset)
+        );
+    }
+    Ok(true)
+}
+
+fn extract(pack: &str, dir: &str) -> io::Result<bool> {
+    let pack = open(pack)?;
+    let dir = Path::new(dir);
+    let mut extracted = 0;
+    for asset in pack.assets() {
+        // don't write outside of the folder for paths like `../file`
+        if Path::new(asset.path)
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+        {
+            eprintln!("skipping asset with an invalid path: {}", asset.path);
+            continue;
+        }
+        let destination = if asset.processed {
+            dir.join("imported_assets").join("Default")
+        } else {
+            dir.join("assets")
+        }
+        .join(asset.path);
+        fs::create_dir_all(destination.parent().unwrap())?;
+        fs::write(destination, asset.data)?;
+        extracted += 1;
+    }
+    println!("extracted {extracted} assets to {}", dir.display());
+    Ok(true)
+}
+
+fn verify(pack: &str, dir: &str, processed_dir: Option<&str>) -> io::Result<bool> {
+    let pack = open(pack)?;
+    let mut matching = true;
+    for (dir, processed) in [(Some(dir), false), (processed_dir, true)] {
+        let Some(dir) = dir else {
+            continue;
+        };
+        let mut on_disk = files(Path::new(dir))?;
+        for asset in pack.assets().iter().filter(|a| a.processed == processed) {
+            match on_disk.remove(asset.path) {
+                None => {
+                    matching = false;
+                    println!("missing from {dir}: {}", display_path(asset));
+                }
+                Some(file) if fs::read(&file)? != asset.data => {
+                    matching = false;
+                    println!("different: {}", display_path(asset));
+                }
+                Some(_) => (),
+            }
+        }
+        for path in on_disk.keys() {
+            matching = false;
+            let kind = if processed { " (processed)" } else { "" };
+            println!("not in pack: {path}{kind}");
+        }
+    }
+    if matching {
+        println!("all assets match");
+    }
+    Ok(matching)
+}
+
+fn diff(old: &str, new: &str) -> io::Result<bool> {
+    let by_path = |pack: &AssetPack| {
+        pack.assets()
+            .iter()
+            .map(|asset| (display_path(asset), *asset))
+            .collect::<BTreeMap<_, _>>()
+    };
+    let (old, new) = (open(old)?, open(new)?);
+    let (old, new) = (by_path(&old), by_path(&new));
+    let mut same = true;
+    for (path, asset) in &old {
+        match new.get(path) {
+            None => {
+                same = false;
+                println!("- {path}");
+            }
+            Some(new_asset) if new_asset.data != asset.data => {
+                same = false;
+                println!(
+                    "~ {path}: {} {} -> {} {}",
+                    asset.data.len(),
+                    hash(asset.data),
+                    new_asset.data.len(),
+                    hash(new_asset.data)
+                );
+            }
+            Some(_) => (),
+        }
+    }
+    for path in new.keys().filter(|path| !old.contains_key(*path)) {
+        same = false;
+        println!("+ {path}");
+    }
+    Ok(same)
+}
+
+/// All files in a folder, by their path relative to it as written in packs.
+fn files(dir: &Path) -> io::Result<BTreeMap<String, PathBuf>> {
+    let mut files = BTreeMap::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(current) = dirs.pop() {
+        for entry in fs::read_dir(current)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                let relative = path
+                    .strip_prefix(dir)
+                    .unwrap()
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/")
+                    .nfc()
+                    .collect();
+                files.insert(relative, path);
+            }
+        }
+    }
+    Ok(files)
+}
@@ -0,0 +1,267 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use bevy_ecs::resource::Resource;
+
+use crate::embedded;
+
+/// Keeps in memory the assets read from the sidecar folder, up to a memory budget, evicting the
+/// least recently used ones first.
+///
+/// Embedded assets are always in memory, but assets too large to be embedded are read from the
+/// sidecar folder every time they are loaded. When inserted as a resource before adding the
+/// [`EmbeddedAssetPlugin`](crate::EmbeddedAssetPlugin), the readers replacing the default source
+/// keep them in this cache, so reloading them doesn't read them again.
+///
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_embedded_assets::{AssetCache, EmbeddedAssetPlugin, PluginMode};
+/// App::new()
+///     .insert_resource(AssetCache::new(256 * 1024 * 1024))
+///     .add_plugins((
+///         EmbeddedAssetPlugin {
+///             mode: PluginMode::ReplaceDefault,
+///         },
+///         DefaultPlugins,
+///     ));
+/// ```
+#[derive(Resource, Clone, Debug)]
+pub struct AssetCache(Arc<Mutex<CacheState>>);
+
+/// Statistics of an [`AssetCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of reads served from the cache.
+    pub hits: u64,
+    /// Number of reads that were not in the cache.
+    pub misses: u64,
+    /// Number of assets removed from the cache to stay under the budget.
+    pub evictions: u64,
+    /// Number of assets in the cache.
+    pub entries: usize,
+    /// Total size in bytes of the assets in the cache.
+    pub size: usize,
+    /// Maximum total size in bytes of the assets in the cache.
+    pub budget: usize,
+}
+
+/// A cached asset by if it's processed and its path.
+type CacheKey = (bool, PathBuf);
+
+#[derive(Debug)]
+struct CacheState {
+    entries: HashMap<CacheKey, (Arc<[u8]>, u64)>,
+    /// Keys of the entries by when they were last used.
+    recent: BTreeMap<u64, CacheKey>,
+    /// Incremented each time an entry is used.
+    tick: u64,
+    stats: CacheStats,
+}
+
+impl CacheState {
+    fn touch(&mut self, key: &CacheKey) {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some((_, used)) = self.entries.get_mut(key) {
+            self.recent.remove(used);
+            *used = tick;
+            self.recent.insert(tick, key.clone());
+        }
+    }
+}
+
+impl AssetCache {
+    /// Create an empty cache that keeps at most `budget` bytes in memory.
+    #[must_use]
+    pub fn new(budget: usize) -> Self {
+        Self(Arc::new(Mutex::new(CacheState {
+            entries: HashMap::new(),
+            recent: BTreeMap::new(),
+            tick: 0,
+            stats: CacheStats {
+                budget,
+                ..CacheStats::default()
+            },
+        })))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CacheState> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Statistics of the cache since it was created.
+    #[must_use]
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    /// Remove all assets from the cache. Statistics are kept.
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.entries.clear();
+        state.recent.clear();
+        state.stats.entries = 0;
+        state.stats.size = 0;
+    }
+
+    /// Read raw assets from the sidecar folder into the cache, for example during a loading
+    /// screen. Paths are relative to the asset folder, and embedded assets are skipped as they are
+    /// already in memory.
+    ///
+    /// This reads the files synchronously, it can be run in a task of the `IoTaskPool`.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if a path is not an embedded asset, or if an asset can't be read
+    /// from the sidecar folder.
+    pub fn preload<P: AsRef<Path>>(&self, paths: impl IntoIterator<Item = P>) -> io::Result<()> {
+        self.preload_from(paths, false)
+    }
+
+    /// Read processed assets from the sidecar folder into the cache, like
+    /// [`AssetCache::preload`] does for raw assets.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if a path is not an embedded processed asset, or if an asset
+    /// can't be read from the sidecar folder.
+    pub fn preload_processed<P: AsRef<Path>>(
+        &self,
+        paths: impl IntoIterator<Item = P>,
+    ) -> io::Result<()> {
+        self.preload_from(paths, true)
+    }
+
+    fn preload_from<P: AsRef<Path>>(
+        &self,
+        paths: impl IntoIterator<Item = P>,
+        processed: bool,
+    ) -> io::Result<()> {
+        for path in paths {
+            let path = path.as_ref();
+            let name = path.to_string_lossy();
+            let asset = if processed {
+                embedded::get_processed(&name)
+            } else {
+                embedded::get(&name)
+            }
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{name} is not an embedded asset"),
+                )
+            })?;
+            if asset.data().is_some() || self.contains(processed, path) {
+                continue;
+            }
+            self.insert(processed, path, asset.read()?.into_owned().into());
+        }
+        Ok(())
+    }
+
+    fn contains(&self, processed: bool, path: &Path) -> bool {
+        self.lock()
+            .entries
+            .contains_key(&(processed, path.to_path_buf()))
+    }
+
+    /// Get an asset from the cache, counting a hit or a miss.
+    pub(crate) fn get(&self, processed: bool, path: &Path) -> Option<Arc<[u8]>> {
+        let mut state = self.lock();
+        let key = (processed, path.to_path_buf());
+        let Some((data, _)) = state.entries.get(&key) else {
+            state.stats.misses += 1;
+            return None;
+        };
+        let data = data.clone();
+        state.stats.hits += 1;
+        state.touch(&key);
+        Some(data)
+    }
+
+    /// If an asset of this size can be kept in the cache.
+    pub(crate) fn fits(&self, len: usize) -> bool {
+        len <= self.lock().stats.budget
+    }
+
+    /// Add an asset to the cache, evicting the least recently used ones to stay under the
+    /// budget. Assets larger than the budget are not cached.
+    pub(crate) fn insert(&self, processed: bool, path: &Path, data: Arc<[u8]>) {
+        let mut state = self.lock();
+        if data.len() > state.stats.budget {
+            return;
+        }
+        let key = (processed, path.to_path_buf());
+        let len = data.len();
+        if let Some((previous, used)) = state.entries.insert(key.clone(), (data, 0)) {
+            state.recent.remove(&used);
+            state.stats.size -= previous.len();
+            state.stats.entries -= 1;
+        }
+        state.stats.size += len;
+        state.stats.entries += 1;
+        state.touch(&key);
+        while state.stats.size > state.stats.budget {
+            let Some((_, oldest)) = state.recent.pop_first() else {
+                break;
+            };
+            if let Some((data, _)) = state.entries.remove(&oldest) {
+                state.stats.size -= data.len();
+                state.stats.entries -= 1;
+                state.stats.evictions += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::AssetCache;
+
+    #[test]
+    fn evict_least_recently_used() {
+        let cache = AssetCache::new(10);
+        cache.insert(false, Path::new("a"), vec![0; 4].into());
+        cache.insert(false, Path::new("b"), vec![0; 4].into());
+        assert!(cache.get(false, Path::new("a")).is_some());
+        cache.insert(false, Path::new("c"), vec![0; 4].into());
+
+        assert!(cache.get(false, Path::new("a")).is_some());
+        assert!(cache.get(false, Path::new("b")).is_none());
+        assert!(cache.get(false, Path::new("c")).is_some());
+        assert!(cache.get(true, Path::new("c")).is_none());
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 3);
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.size, 8);
+    }
+
+    #[test]
+    fn skip_assets_over_budget() {
+        let cache = AssetCache::new(10);
+        cache.insert(false, Path::new("a"), vec![0; 4].into());
+        cache.insert(false, Path::new("large"), vec![0; 11].into());
+        assert!(cache.get(false, Path::new("large")).is_none());
+        assert!(cache.get(false, Path::new("a")).is_some());
+
+        cache.clear();
+        assert_eq!(cache.stats().size, 0);
+        assert!(cache.get(false, Path::new("a")).is_none());
+    }
+
+    #[test]
+    fn preload_skips_embedded_assets() {
+        let cache = AssetCache::new(1024);
+        cache.preload(["example_asset.test"]).unwrap();
+        assert_eq!(cache.stats().entries, 0);
+        assert!(cache.preload(["missing.test"]).is_err());
+    }
+}
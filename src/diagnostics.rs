@@ -0,0 +1,269 @@
+use std::{
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    task::{Context, Poll},
+};
+
+use bevy_app::{App, Update};
+use bevy_asset::io::{AssetReaderError, Reader, ReaderNotSeekableError, SeekableReader};
+use bevy_diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy_ecs::{resource::Resource, system::Res};
+use futures_io::{AsyncRead, AsyncSeek, SeekFrom};
+
+/// Counts the reads of assets when replacing the default source, to know how many are embedded
+/// and how many come from the fallback.
+///
+/// This is only available when the `diagnostics` cargo feature is enabled. When replacing the
+/// default asset source, the [`EmbeddedAssetPlugin`](crate::EmbeddedAssetPlugin) inserts it as a
+/// resource, and publishes its counts as diagnostics under the paths of the associated constants,
+/// for example to display them with the `LogDiagnosticsPlugin`.
+///
+/// Reads of `.meta` files from the fallback are counted separately from reads of assets, embedded
+/// `.meta` files are not counted. Assets in the sidecar folder are counted as embedded.
+#[derive(Resource, Clone, Default, Debug)]
+pub struct EmbeddedAssetStats(Arc<Counters>);
+
+#[derive(Default, Debug)]
+struct Counters {
+    reads: AtomicU64,
+    misses: AtomicU64,
+    fallback_hits: AtomicU64,
+    fallback_errors: AtomicU64,
+    fallback_meta_hits: AtomicU64,
+    fallback_meta_errors: AtomicU64,
+    embedded_bytes: AtomicU64,
+    fallback_bytes: AtomicU64,
+}
+
+/// Counts of an [`EmbeddedAssetStats`] at one point in time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReadStats {
+    /// Number of assets read, embedded or not.
+    pub reads: u64,
+    /// Number of assets read that are not embedded.
+    pub misses: u64,
+    /// Number of assets not embedded that were read from the fallback.
+    pub fallback_hits: u64,
+    /// Number of assets not embedded that the fallback failed to read, including the ones it
+    /// didn't find.
+    pub fallback_errors: u64,
+    /// Number of `.meta` files of assets not embedded that were read from the fallback.
+    pub fallback_meta_hits: u64,
+    /// Number of `.meta` files of assets not embedded that the fallback failed to read, including
+    /// the ones it didn't find.
+    pub fallback_meta_errors: u64,
+    /// Number of bytes read from embedded assets.
+    pub embedded_bytes: u64,
+    /// Number of bytes read from the fallback, assets and `.meta` files.
+    pub fallback_bytes: u64,
+}
+
+impl EmbeddedAssetStats {
+    /// Diagnostic with the number of assets read.
+    pub const READS: DiagnosticPath = DiagnosticPath::const_new("embedded_assets/reads");
+    /// Diagnostic with the number of assets read that are not embedded.
+    pub const MISSES: DiagnosticPath = DiagnosticPath::const_new("embedded_assets/misses");
+    /// Diagnostic with the number of assets read from the fallback.
+    pub const FALLBACK_HITS: DiagnosticPath =
+        DiagnosticPath::const_new("embedded_assets/fallback_hits");
+    /// Diagnostic with the number of assets the fallback failed to read.
+    pub const FALLBACK_ERRORS: DiagnosticPath =
+        DiagnosticPath::const_new("embedded_assets/fallback_errors");
+    /// Diagnostic with the number of `.meta` files read from the fallback.
+    pub const FALLBACK_META_HITS: DiagnosticPath =
+        DiagnosticPath::const_new("embedded_assets/fallback_meta_hits");
+    /// Diagnostic with the number of `.meta` files the fallback failed to read.
+    pub const FALLBACK_META_ERRORS: DiagnosticPath =
+        DiagnosticPath::const_new("embedded_assets/fallback_meta_errors");
+    /// Diagnostic with the number of bytes read from embedded assets.
+    pub const EMBEDDED_BYTES: DiagnosticPath =
+        DiagnosticPath::const_new("embedded_assets/embedded_bytes");
+    /// Diagnostic with the number of bytes read from the fallback, assets and `.meta` files.
+    pub const FALLBACK_BYTES: DiagnosticPath =
+        DiagnosticPath::const_new("embedded_assets/fallback_bytes");
+
+    /// Current counts.
+    #[must_use]
+    pub fn snapshot(&self) -> ReadStats {
+        let counters = &self.0;
+        ReadStats {
+            reads: counters.reads.load(Ordering::Relaxed),
+            misses: counters.misses.load(Ordering::Relaxed),
+            fallback_hits: counters.fallback_hits.load(Ordering::Relaxed),
+            fallback_errors: counters.fallback_errors.load(Ordering::Relaxed),
+            fallback_meta_hits: counters.fallback_meta_hits.load(Ordering::Relaxed),
+            fallback_meta_errors: counters.fallback_meta_errors.load(Ordering::Relaxed),
+            embedded_bytes: counters.embedded_bytes.load(Ordering::Relaxed),
+            fallback_bytes: counters.fallback_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Count a read of an embedded asset, and the bytes read from it.
+    pub(crate) fn embedded<'a>(&'a self, reader: Box<dyn Reader + 'a>) -> Box<dyn Reader + 'a> {
+        self.0.reads.fetch_add(1, Ordering::Relaxed);
+        Box::new(CountingReader {
+            inner: reader,
+            bytes: &self.0.embedded_bytes,
+        })
+    }
+
+    /// Count a read of an asset that is not embedded, and the bytes read from the fallback.
+    pub(crate) fn fallback<'a>(
+        &'a self,
+        result: Result<Box<dyn Reader + 'a>, AssetReaderError>,
+    ) -> Result<Box<dyn Reader + 'a>, AssetReaderError> {
+        self.miss();
+        self.count_fallback(result, &self.0.fallback_hits, &self.0.fallback_errors)
+    }
+
+    /// Count a read of the `.meta` file of an asset that is not embedded, and the bytes read from
+    /// the fallback.
+    pub(crate) fn fallback_meta<'a>(
+        &'a self,
+        result: Result<Box<dyn Reader + 'a>, AssetReaderError>,
+    ) -> Result<Box<dyn Reader + 'a>, AssetReaderError> {
+        self.count_fallback(
+            result,
+            &self.0.fallback_meta_hits,
+            &self.0.fallback_meta_errors,
+        )
+    }
+
+    fn count_fallback<'a>(
+        &'a self,
+        result: Result<Box<dyn Reader + 'a>, AssetReaderError>,
+        hits: &AtomicU64,
+        errors: &AtomicU64,
+    ) -> Result<Box<dyn Reader + 'a>, AssetReaderError> {
+        match result {
+            Ok(reader) => {
+                hits.fetch_add(1, Ordering::Relaxed);
+                Ok(Box::new(CountingReader {
+                    inner: reader,
+                    bytes: &self.0.fallback_bytes,
+                }))
+            }
+            Err(err) => {
+                errors.fetch_add(1, Ordering::Relaxed);
+                Err(err)
+            }
+        }
+    }
+
+    /// Count a read of an asset that is not embedded, without a fallback.
+    pub(crate) fn miss(&self) {
+        self.0.reads.fetch_add(1, Ordering::Relaxed);
+        self.0.misses.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Register the diagnostics, and the system publishing them.
+pub(crate) fn setup(app: &mut App) -> EmbeddedAssetStats {
+    for path in [
+        EmbeddedAssetStats::READS,
+        EmbeddedAssetStats::MISSES,
+        EmbeddedAssetStats::FALLBACK_HITS,
+        EmbeddedAssetStats::FALLBACK_ERRORS,
+        EmbeddedAssetStats::FALLBACK_META_HITS,
+        EmbeddedAssetStats::FALLBACK_META_ERRORS,
+    ] {
+        app.register_diagnostic(Diagnostic::new(path));
+    }
+    for path in [
+        EmbeddedAssetStats::EMBEDDED_BYTES,
+        EmbeddedAssetStats::FALLBACK_BYTES,
+    ] {
+        app.register_diagnostic(Diagnostic::new(path).with_suffix(" B"));
+    }
+    app.add_systems(Update, publish);
+    app.world_mut()
+        .get_resource_or_init::<EmbeddedAssetStats>()
+        .clone()
+}
+
+#[allow(clippy::needless_pass_by_value, clippy::cast_precision_loss)]
+fn publish(mut diagnostics: Diagnostics, stats: Res<EmbeddedAssetStats>) {
+    let stats = stats.snapshot();
+    for (path, value) in [
+        (EmbeddedAssetStats::READS, stats.reads),
+        (EmbeddedAssetStats::MISSES, stats.misses),
+        (EmbeddedAssetStats::FALLBACK_HITS, stats.fallback_hits),
+        (EmbeddedAssetStats::FALLBACK_ERRORS, stats.fallback_errors),
+        (
+            EmbeddedAssetStats::FALLBACK_META_HITS,
+            stats.fallback_meta_hits,
+        ),
+        (
+            EmbeddedAssetStats::FALLBACK_META_ERRORS,
+            stats.fallback_meta_errors,
+        ),
+        (EmbeddedAssetStats::EMBEDDED_BYTES, stats.embedded_bytes),
+        (EmbeddedAssetStats::FALLBACK_BYTES, stats.fallback_bytes),
+    ] {
+        diagnostics.add_measurement(&path, || value as f64);
+    }
+}
+
+/// A [`Reader`] counting the bytes read from another one.
+struct CountingReader<'a> {
+    inner: Box<dyn Reader + 'a>,
+    bytes: &'a AtomicU64,
+}
+
+impl Reader for CountingReader<'_> {
+    fn read_to_end<'a>(
+        &'a mut self,
+        buf: &'a mut Vec<u8>,
+    ) -> bevy_asset::io::StackFuture<
+        'a,
+        std::io::Result<usize>,
+        { bevy_asset::io::STACK_FUTURE_SIZE },
+    > {
+        let bytes = self.bytes;
+        // boxed so that the wrapping future fits in a `StackFuture`
+        let future = Box::pin(self.inner.read_to_end(buf));
+        bevy_asset::io::StackFuture::from(async move {
+            let read = future.await?;
+            bytes.fetch_add(read as u64, Ordering::Relaxed);
+            Ok(read)
+        })
+    }
+
+    fn seekable(&mut self) -> Result<&mut dyn SeekableReader, ReaderNotSeekableError> {
+        self.inner.seekable()?;
+        Ok(self)
+    }
+}
+
+impl AsyncRead for CountingReader<'_> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<futures_io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(read)) = poll {
+            this.bytes.fetch_add(read as u64, Ordering::Relaxed);
+        }
+        poll
+    }
+}
+
+impl AsyncSeek for CountingReader<'_> {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<futures_io::Result<u64>> {
+        match self.get_mut().inner.seekable() {
+            Ok(seekable) => Pin::new(seekable).poll_seek(cx, pos),
+            Err(_) => Poll::Ready(Err(futures_io::Error::other(
+                "the reader doesn't support seeking",
+            ))),
+        }
+    }
+}
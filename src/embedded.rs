@@ -0,0 +1,134 @@
+//! Access to the embedded assets without Bevy.
+//!
+//! This works without an `App`, and without the `bevy` cargo feature, for tools or servers that
+//! need to read the same assets as the game:
+//!
+//! ```rust
+//! # use bevy_embedded_assets::embedded;
+//! let asset = embedded::get("subdir/other_asset.test").unwrap();
+//! assert_eq!(asset.data(), Some(&b"in subdirectory"[..]));
+//! ```
+//!
+//! Paths are relative to the asset folder, with `/` as separator, and must match exactly.
+
+use std::{borrow::Cow, fs, io};
+
+use crate::sidecar;
+
+include!(concat!(env!("OUT_DIR"), "/embedded_assets.rs"));
+
+/// An asset found by the build script.
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddedAsset {
+    path: &'static str,
+    processed: bool,
+    data: Option<&'static [u8]>,
+    size: usize,
+    hash: &'static str,
+    align: usize,
+}
+
+impl EmbeddedAsset {
+    /// Path of the asset, relative to the asset folder.
+    #[must_use]
+    pub fn path(&self) -> &'static str {
+        self.path
+    }
+
+    /// If this is a processed asset, or a raw one.
+    #[must_use]
+    pub fn is_processed(&self) -> bool {
+        self.processed
+    }
+
+    /// Content of the asset, or `None` if it was too large to be embedded and is in the sidecar
+    /// folder.
+    #[must_use]
+    pub fn data(&self) -> Option<&'static [u8]> {
+        self.data
+    }
+
+    /// Content of the asset, read from the sidecar folder if it was too large to be embedded.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if the asset can't be read from the sidecar folder.
+    pub fn read(&self) -> io::Result<Cow<'static, [u8]>> {
+        match self.data {
+            Some(data) => Ok(Cow::Borrowed(data)),
+            None => fs::read(sidecar::source_dir(self.processed).join(self.path)).map(Cow::Owned),
+        }
+    }
+
+    /// Size in bytes of the asset, embedded or in the sidecar folder.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// BLAKE3 hash of the content of the asset, in hexadecimal, as in the report written by the
+    /// build script.
+    #[must_use]
+    pub fn hash(&self) -> &'static str {
+        self.hash
+    }
+
+    /// Alignment in bytes guaranteed for the embedded [`data`](Self::data), set at build time by
+    /// `BEVY_EMBEDDED_ASSETS_ALIGN`. This is `1` when the data is not aligned.
+    ///
+    /// Data aligned to the size of a type can be cast to a slice of it without copying, for
+    /// example with `bytemuck::cast_slice` for a `&[u32]` when this is at least `4`.
+    #[must_use]
+    pub fn align(&self) -> usize {
+        self.align
+    }
+}
+
+/// All embedded assets, raw ones then processed ones.
+#[must_use]
+pub fn assets() -> &'static [EmbeddedAsset] {
+    EMBEDDED_ASSETS
+}
+
+/// All embedded raw assets, sorted by path.
+#[must_use]
+pub fn raw_assets() -> &'static [EmbeddedAsset] {
+    &EMBEDDED_ASSETS[..EMBEDDED_ASSETS.partition_point(|asset| !asset.processed)]
+}
+
+/// All embedded processed assets, sorted by path.
+#[must_use]
+pub fn processed_assets() -> &'static [EmbeddedAsset] {
+    &EMBEDDED_ASSETS[EMBEDDED_ASSETS.partition_point(|asset| !asset.processed)..]
+}
+
+/// Get a raw asset.
+#[must_use]
+pub fn get(path: &str) -> Option<&'static EmbeddedAsset> {
+    find(raw_assets(), path)
+}
+
+/// Get a processed asset.
+#[must_use]
+pub fn get_processed(path: &str) -> Option<&'static EmbeddedAsset> {
+    find(processed_assets(), path)
+}
+
+/// Raw assets in a folder, at any depth. An empty path lists all raw assets.
+pub fn read_dir(dir: &str) -> impl Iterator<Item = &'static EmbeddedAsset> {
+    let dir = dir.trim_end_matches('/');
+    raw_assets().iter().filter(move |asset| {
+        dir.is_empty()
+            || asset
+                .path
+                .strip_prefix(dir)
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+fn find(assets: &'static [EmbeddedAsset], path: &str) -> Option<&'static EmbeddedAsset> {
+    assets
+        .binary_search_by(|asset| asset.path.cmp(path))
+        .ok()
+        .map(|i| &assets[i])
+}
@@ -0,0 +1,158 @@
+include!(concat!(env!("OUT_DIR"), "/embedded_paths.rs"));
+
+/// Maximum length of paths compared when looking for a suggestion.
+const MAX_COMPARED_LENGTH: usize = 256;
+
+/// Check that a path is an embedded asset path during compilation, which is how
+/// [`embedded_path!`](crate::embedded_path) fails to compile for an unknown path.
+///
+/// When the path is not found, the closest embedded path is suggested.
+#[doc(hidden)]
+#[must_use]
+pub const fn check_embedded_path(path: &'static str) -> &'static str {
+    let mut i = 0;
+    while i < EMBEDDED_PATHS.len() {
+        if str_eq(EMBEDDED_PATHS[i], path) {
+            return path;
+        }
+        i += 1;
+    }
+
+    let path = path.as_bytes();
+    // Only suggest paths that are close enough to be a typo
+    let mut closest = usize::MAX;
+    let mut closest_distance = path.len() / 3 + 2;
+    let mut i = 0;
+    while i < EMBEDDED_PATHS.len() {
+        let candidate = EMBEDDED_PATHS[i].as_bytes();
+        if candidate.len().abs_diff(path.len()) < closest_distance {
+            let distance = distance(candidate, path);
+            if distance < closest_distance {
+                closest = i;
+                closest_distance = distance;
+            }
+        }
+        i += 1;
+    }
+    unknown_embedded_path(closest)
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Levenshtein distance between two byte strings, considering only their first
+/// [`MAX_COMPARED_LENGTH`] bytes.
+const fn distance(a: &[u8], b: &[u8]) -> usize {
+    let a_len = if a.len() < MAX_COMPARED_LENGTH {
+        a.len()
+    } else {
+        MAX_COMPARED_LENGTH
+    };
+    let b_len = if b.len() < MAX_COMPARED_LENGTH {
+        b.len()
+    } else {
+        MAX_COMPARED_LENGTH
+    };
+
+    let mut previous = [0; MAX_COMPARED_LENGTH + 1];
+    let mut current = [0; MAX_COMPARED_LENGTH + 1];
+    let mut j = 0;
+    while j <= b_len {
+        previous[j] = j;
+        j += 1;
+    }
+    let mut i = 1;
+    while i <= a_len {
+        current[0] = i;
+        let mut j = 1;
+        while j <= b_len {
+            let substitution = previous[j - 1] + if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let deletion = previous[j] + 1;
+            let insertion = current[j - 1] + 1;
+            let mut best = substitution;
+            if deletion < best {
+                best = deletion;
+            }
+            if insertion < best {
+                best = insertion;
+            }
+            current[j] = best;
+            j += 1;
+        }
+        previous = current;
+        i += 1;
+    }
+    previous[b_len]
+}
+
+/// Get the path of an embedded asset, checked during compilation.
+///
+/// If the path is not one of the embedded assets, compilation fails with a suggestion for the
+/// closest embedded path.
+///
+/// With only a path, it expands to the path as a `&'static str`:
+///
+/// ```rust
+/// # use bevy_embedded_assets::embedded_path;
+/// let path: &'static str = embedded_path!("example_asset.test");
+/// assert_eq!(path, "example_asset.test");
+/// ```
+///
+/// Prefixed by the [`PluginMode`](crate::PluginMode) used, it expands to an `AssetPath` with the
+/// asset source matching that mode: `embedded://` for `AutoLoad`, the default source for
+/// `ReplaceDefault`, `ReplaceAndFallback` and `ReplaceAndFallbackWithProcessed`.
+///
+/// ```rust
+/// # use bevy::asset::{AssetPath, io::AssetSourceId};
+/// # use bevy_embedded_assets::embedded_path;
+/// # #[cfg(not(feature = "bevy"))]
+/// # fn main() {}
+/// # #[cfg(feature = "bevy")]
+/// # fn main() {
+/// let path: AssetPath<'static> = embedded_path!(AutoLoad, "subdir/other_asset.test");
+/// assert_eq!(path.source(), &AssetSourceId::from("embedded"));
+/// # }
+/// ```
+///
+/// A typo is caught during compilation:
+///
+/// ```rust,compile_fail
+/// # use bevy_embedded_assets::embedded_path;
+/// // error: this path is not an embedded asset, did you mean `example_asset.test`?
+/// let path = embedded_path!("exemple_asset.test");
+/// ```
+#[macro_export]
+macro_rules! embedded_path {
+    ($path:literal) => {{
+        const PATH: &str = $crate::__private::check_embedded_path($path);
+        PATH
+    }};
+    (AutoLoad, $path:literal) => {{
+        const _: &str = $crate::__private::check_embedded_path($path);
+        $crate::__private::AssetPath::from(concat!("embedded://", $path))
+    }};
+    (ReplaceDefault, $path:literal) => {{
+        const PATH: &str = $crate::__private::check_embedded_path($path);
+        $crate::__private::AssetPath::from(PATH)
+    }};
+    (ReplaceAndFallback, $path:literal) => {{
+        const PATH: &str = $crate::__private::check_embedded_path($path);
+        $crate::__private::AssetPath::from(PATH)
+    }};
+    (ReplaceAndFallbackWithProcessed, $path:literal) => {{
+        const PATH: &str = $crate::__private::check_embedded_path($path);
+        $crate::__private::AssetPath::from(PATH)
+    }};
+}
@@ -1,5 +1,10 @@
 #![allow(clippy::needless_doctest_main)]
-#![doc = include_str!("../README.md")]
+// the examples of the README use the plugin
+#![cfg_attr(feature = "bevy", doc = include_str!("../README.md"))]
+#![cfg_attr(
+    not(feature = "bevy"),
+    doc = "Embed your asset folder inside your binary, read through the [`embedded`] module."
+)]
 #![warn(
     missing_debug_implementations,
     missing_copy_implementations,
@@ -13,18 +18,22 @@
     clippy::pedantic
 )]
 
-use std::path::PathBuf;
-
-use bevy_app::App;
-use bevy_app::Plugin;
-use bevy_asset::AssetPlugin;
-use bevy_asset::io::embedded::EmbeddedAssetRegistry;
-use bevy_ecs::resource::Resource;
+#[cfg(feature = "bevy")]
+use {
+    bevy_app::{App, Plugin},
+    bevy_asset::{
+        AssetMetaCheck, AssetPath, AssetPlugin, AssetServerMode,
+        io::{AssetSourceId, embedded::EmbeddedAssetRegistry},
+    },
+    bevy_ecs::resource::Resource,
+    log::warn,
+    std::path::PathBuf,
+};
 #[cfg(feature = "default-source")]
 use {
     bevy_asset::{
         AssetApp,
-        io::{AssetSource, AssetSourceBuilder, AssetSourceId},
+        io::{AssetSource, AssetSourceBuilder},
     },
     log::error,
 };
@@ -32,9 +41,43 @@ use {
 #[cfg(feature = "default-source")]
 mod asset_reader;
 #[cfg(feature = "default-source")]
-pub use {asset_reader::DataReader, asset_reader::EmbeddedAssetReader};
+mod cache;
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
+pub mod embedded;
+mod embedded_path;
+#[cfg(feature = "pack")]
+mod pack;
+#[cfg(feature = "record-access")]
+mod recorder;
+mod sidecar;
+#[cfg(feature = "default-source")]
+pub use cache::{AssetCache, CacheStats};
+#[cfg(feature = "diagnostics")]
+pub use diagnostics::{EmbeddedAssetStats, ReadStats};
+#[cfg(feature = "pack")]
+pub use pack::{AssetPack, PackedAsset};
+#[cfg(feature = "record-access")]
+pub use recorder::AccessRecorder;
+#[cfg(feature = "default-source")]
+pub use {asset_reader::DataReader, asset_reader::EmbeddedAssetReader, asset_reader::ExtractMode};
 
-include!(concat!(env!("OUT_DIR"), "/include_all_assets.rs"));
+/// Paths of all embedded assets, as constants in modules mirroring the asset folder.
+///
+/// This is only available when the `path-constants` cargo feature is enabled.
+///
+/// ```rust,ignore
+/// # use bevy::prelude::*;
+/// # fn system(asset_server: Res<AssetServer>) {
+/// // loads "textures/player.png"
+/// let handle: Handle<Image> = asset_server.load(bevy_embedded_assets::assets::textures::PLAYER_PNG);
+/// # }
+/// ```
+#[cfg(feature = "path-constants")]
+#[allow(clippy::doc_markdown)]
+pub mod assets {
+    include!(concat!(env!("OUT_DIR"), "/asset_paths.rs"));
+}
 
 /// Bevy plugin to embed all your asset folder.
 ///
@@ -68,6 +111,9 @@ include!(concat!(env!("OUT_DIR"), "/include_all_assets.rs"));
 /// # use bevy_embedded_assets::{EmbeddedAssetPlugin, PluginMode};
 /// # #[derive(Asset, TypePath)]
 /// # pub struct MyAsset;
+/// # #[cfg(not(feature = "default-source"))]
+/// # fn main() {}
+/// # #[cfg(feature = "default-source")]
 /// # fn main() {
 /// # let mut app = App::new();
 /// app.add_plugins((EmbeddedAssetPlugin { mode: PluginMode::ReplaceDefault }, DefaultPlugins));
@@ -77,7 +123,8 @@ include!(concat!(env!("OUT_DIR"), "/include_all_assets.rs"));
 /// # }
 /// ```
 ///
-///
+/// This is only available when the `bevy` cargo feature is enabled.
+#[cfg(feature = "bevy")]
 #[allow(clippy::module_name_repetitions)]
 #[derive(Default, Debug, Clone)]
 pub struct EmbeddedAssetPlugin {
@@ -86,42 +133,322 @@ pub struct EmbeddedAssetPlugin {
 }
 
 /// How [`EmbeddedAssetPlugin`] should behave.
+#[cfg(feature = "bevy")]
 #[derive(Debug, Clone, Default)]
 #[allow(missing_copy_implementations)]
 pub enum PluginMode {
     /// Embed the assets folder and make the files available through the `embedded://` source.
+    ///
+    /// Assets too large to be embedded, kept in the sidecar folder, are not available.
     #[default]
     AutoLoad,
     /// Replace the default asset source with an embedded source.
     ///
     /// In this mode, listing files in a directory will work in wasm.
+    ///
+    /// When the build script embeds processed assets separately, they are used when the
+    /// `AssetPlugin` is in `AssetMode::Processed`.
     #[cfg(feature = "default-source")]
     ReplaceDefault,
     /// Replace the default asset source with an embedded source. If a file is not present at build
     /// time, fallback to the default source for the current platform.
     ///
     /// In this mode, listing files in a directory will work in wasm.
+    ///
+    /// When the build script embeds processed assets separately, they are used when the
+    /// `AssetPlugin` is in `AssetMode::Processed`, falling back to `imported_assets/Default`.
     #[cfg(feature = "default-source")]
     ReplaceAndFallback {
         /// The default file path to use (relative to the project root). `"assets"` is the
         /// standard value in Bevy.
         path: String,
     },
+    /// Like [`PluginMode::ReplaceAndFallback`], with a custom fallback path for processed assets
+    /// when the `AssetPlugin` is in `AssetMode::Processed`.
+    #[cfg(feature = "default-source")]
+    ReplaceAndFallbackWithProcessed {
+        /// The default file path to use (relative to the project root). `"assets"` is the
+        /// standard value in Bevy.
+        path: String,
+        /// The default file path to use for processed assets (relative to the project root).
+        /// `"imported_assets/Default"` is the standard value in Bevy.
+        processed_path: String,
+    },
+    /// Replace the default asset source with the assets of a pack file written by the build
+    /// script, memory-mapped instead of embedded in the executable.
+    ///
+    /// This is only available when the `pack` cargo feature is enabled. A relative `path` is
+    /// looked up next to the executable. See [`AssetPack`] for details.
+    #[cfg(feature = "pack")]
+    Pack {
+        /// Path to the pack file. `"assets.pack"` is the name of the file written by the build
+        /// script.
+        path: String,
+    },
+    /// Replace the default asset source with the assets of a pack appended to the running
+    /// executable with [`AssetPack::append`], without building it again.
+    ///
+    /// This is only available when the `pack` cargo feature is enabled.
+    #[cfg(feature = "pack")]
+    AppendedPack,
 }
 
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::embedded_path::check_embedded_path;
+    #[cfg(feature = "bevy")]
+    pub use bevy_asset::AssetPath;
+}
+
+#[cfg(feature = "bevy")]
 #[derive(Resource, Default)]
 struct AllTheEmbedded;
 
+#[cfg(feature = "bevy")]
 trait EmbeddedRegistry {
     fn insert_included_asset(&mut self, name: &'static str, bytes: &'static [u8]);
+    /// Insert an asset that is too large to be embedded, and is in the sidecar folder.
+    fn insert_sidecar_asset(&mut self, name: &'static str, size: usize);
 }
 
+#[cfg(feature = "bevy")]
 impl EmbeddedRegistry for &mut EmbeddedAssetRegistry {
     fn insert_included_asset(&mut self, name: &str, bytes: &'static [u8]) {
         self.insert_asset(PathBuf::new(), std::path::Path::new(name), bytes);
     }
+
+    fn insert_sidecar_asset(&mut self, name: &'static str, _size: usize) {
+        // the `embedded://` source only serves data in memory, reading large files would keep them
+        // all in memory for the whole run
+        warn!(
+            "asset {name} is not embedded because of its size, it is not available with PluginMode::AutoLoad"
+        );
+    }
+}
+
+#[cfg(feature = "bevy")]
+impl EmbeddedRegistry for &mut Vec<&'static str> {
+    fn insert_included_asset(&mut self, name: &'static str, _bytes: &'static [u8]) {
+        self.push(name);
+    }
+
+    fn insert_sidecar_asset(&mut self, name: &'static str, _size: usize) {
+        self.push(name);
+    }
+}
+
+/// Folder of processed assets in Bevy, used as the fallback of processed assets by
+/// [`PluginMode::ReplaceAndFallback`].
+#[cfg(feature = "default-source")]
+const DEFAULT_PROCESSED_FILE_PATH: &str = "imported_assets/Default";
+
+/// Replace the default source with embedded assets, falling back to the given folders.
+#[cfg(feature = "default-source")]
+fn replace_and_fallback(app: &mut App, path: &str, processed_path: &str) {
+    if app.is_plugin_added::<AssetPlugin>() {
+        error!(
+            "plugin EmbeddedAssetPlugin must be added before plugin AssetPlugin when replacing the default asset source"
+        );
+    }
+    let path = path.to_string();
+    let processed_path = processed_path.to_string();
+    let setup = ReaderSetup::new(app);
+    let processed_setup = setup.clone();
+    app.register_asset_source(
+        AssetSourceId::Default,
+        AssetSourceBuilder::new(move || {
+            Box::new(setup.apply(EmbeddedAssetReader::preloaded_with_default(
+                AssetSource::get_default_reader(path.clone()),
+            )))
+        })
+        .with_processed_reader(move || {
+            Box::new(
+                processed_setup.apply(EmbeddedAssetReader::preloaded_processed_with_default(
+                    AssetSource::get_default_reader(processed_path.clone()),
+                )),
+            )
+        }),
+    );
+}
+
+/// Shared state given to each [`EmbeddedAssetReader`] created when replacing the default source.
+#[cfg(feature = "default-source")]
+#[derive(Clone)]
+struct ReaderSetup {
+    cache: Option<AssetCache>,
+    #[cfg(feature = "record-access")]
+    recorder: AccessRecorder,
+    #[cfg(feature = "diagnostics")]
+    stats: EmbeddedAssetStats,
+}
+
+#[cfg(feature = "default-source")]
+impl ReaderSetup {
+    fn new(app: &mut App) -> Self {
+        #[cfg(feature = "record-access")]
+        app.add_systems(bevy_app::Last, recorder::write_on_exit);
+        Self {
+            cache: app.world().get_resource::<AssetCache>().cloned(),
+            #[cfg(feature = "record-access")]
+            recorder: app
+                .world_mut()
+                .get_resource_or_init::<AccessRecorder>()
+                .clone(),
+            #[cfg(feature = "diagnostics")]
+            stats: diagnostics::setup(app),
+        }
+    }
+
+    fn apply(&self, reader: EmbeddedAssetReader) -> EmbeddedAssetReader {
+        let reader = match &self.cache {
+            Some(cache) => reader.with_cache(cache.clone()),
+            None => reader,
+        };
+        #[cfg(feature = "record-access")]
+        let reader = reader.with_recorder(self.recorder.clone());
+        #[cfg(feature = "diagnostics")]
+        let reader = reader.with_stats(self.stats.clone());
+        reader
+    }
+}
+
+/// Get the embedded data of an asset without copying it, from its path in the default source or
+/// the `embedded://` source.
+///
+/// Loaders can use it with the path of the asset they are loading, and the mode of the
+/// `AssetServer` they can keep when created with `FromWorld`, to avoid reading data that is
+/// already in memory:
+///
+/// ```rust
+/// # use bevy::asset::{AssetServerMode, LoadContext};
+/// # fn load(load_context: &LoadContext, mode: AssetServerMode) {
+/// if let Some(bytes) = bevy_embedded_assets::embedded_bytes(load_context.path(), mode) {
+///     // use the `&'static [u8]` directly
+/// }
+/// # }
+/// ```
+///
+/// This returns `None` if the asset is not embedded, was too large to be embedded, or is from
+/// another source. In the default source, the data is the one of the processed asset when the
+/// mode is `AssetServerMode::Processed` and processed assets were embedded separately.
+///
+/// With the `default-source` feature, the path is resolved like the readers replacing the default
+/// source do, so `./asset.png` finds `asset.png`. Without it, the path must be the embedded one.
+///
+/// The data is aligned as reported by [`embedded::EmbeddedAsset::align`].
+#[cfg(feature = "bevy")]
+#[must_use]
+pub fn embedded_bytes(path: &AssetPath, mode: AssetServerMode) -> Option<&'static [u8]> {
+    if path.source() != &AssetSourceId::Default && path.source() != &AssetSourceId::from("embedded")
+    {
+        return None;
+    }
+    let processed =
+        path.source() == &AssetSourceId::Default && matches!(mode, AssetServerMode::Processed);
+    #[cfg(feature = "default-source")]
+    {
+        asset_reader::embedded_data(path.path(), processed)
+    }
+    #[cfg(not(feature = "default-source"))]
+    {
+        let path = path.path().to_string_lossy();
+        if processed && !embedded::processed_assets().is_empty() {
+            embedded::get_processed(&path)
+        } else {
+            embedded::get(&path)
+        }?
+        .data()
+    }
+}
+
+/// Insert all raw assets in a registry.
+#[cfg(feature = "bevy")]
+fn include_all_assets(registry: impl EmbeddedRegistry) {
+    include_assets(registry, embedded::raw_assets());
+}
+
+/// Insert all processed assets in a registry.
+#[cfg(feature = "bevy")]
+fn include_all_processed_assets(registry: impl EmbeddedRegistry) {
+    include_assets(registry, embedded::processed_assets());
+}
+
+#[cfg(feature = "bevy")]
+fn include_assets(mut registry: impl EmbeddedRegistry, assets: &'static [embedded::EmbeddedAsset]) {
+    for asset in assets {
+        match asset.data() {
+            Some(data) => registry.insert_included_asset(asset.path(), data),
+            None => registry.insert_sidecar_asset(asset.path(), asset.size()),
+        }
+    }
+}
+
+#[cfg(feature = "bevy")]
+impl EmbeddedAssetPlugin {
+    /// An [`AssetMetaCheck`] that only looks up the meta files of assets that have one embedded,
+    /// using the asset source matching the mode of this plugin.
+    ///
+    /// Setting it on the `AssetPlugin` avoids requesting meta files that are known to be absent,
+    /// like HTTP requests on the web. When using [`PluginMode::ReplaceAndFallback`], meta files of
+    /// assets that are not embedded will be ignored.
+    ///
+    /// ```rust
+    /// # use bevy::prelude::*;
+    /// # use bevy_embedded_assets::{EmbeddedAssetPlugin, PluginMode};
+    /// # #[cfg(not(feature = "default-source"))]
+    /// # fn main() {}
+    /// # #[cfg(feature = "default-source")]
+    /// # fn main() {
+    /// # let mut app = App::new();
+    /// let embedded = EmbeddedAssetPlugin { mode: PluginMode::ReplaceDefault };
+    /// app.add_plugins((
+    ///     embedded.clone(),
+    ///     DefaultPlugins.set(AssetPlugin {
+    ///         meta_check: embedded.meta_check(),
+    ///         ..default()
+    ///     }),
+    /// ));
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn meta_check(&self) -> AssetMetaCheck {
+        let mut embedded = vec![];
+        match &self.mode {
+            #[cfg(feature = "pack")]
+            PluginMode::Pack { .. } | PluginMode::AppendedPack => {
+                if let Ok(pack) = self.open_pack() {
+                    embedded.extend(pack.paths());
+                }
+            }
+            _ => {
+                include_all_assets(&mut embedded);
+                include_all_processed_assets(&mut embedded);
+            }
+        }
+        AssetMetaCheck::Paths(
+            embedded
+                .into_iter()
+                .filter_map(|path| path.strip_suffix(".meta"))
+                .map(|path| match self.mode {
+                    PluginMode::AutoLoad => AssetPath::from(path).with_source("embedded"),
+                    #[cfg(feature = "default-source")]
+                    _ => AssetPath::from(path),
+                })
+                .collect(),
+        )
+    }
+
+    #[cfg(feature = "pack")]
+    fn open_pack(&self) -> std::io::Result<AssetPack> {
+        match &self.mode {
+            PluginMode::AppendedPack => AssetPack::from_current_exe(),
+            PluginMode::Pack { path } => AssetPack::open(path),
+            _ => unreachable!("not reading from a pack"),
+        }
+    }
 }
 
+#[cfg(feature = "bevy")]
 impl Plugin for EmbeddedAssetPlugin {
     fn build(&self, app: &mut App) {
         match &self.mode {
@@ -139,27 +466,48 @@ impl Plugin for EmbeddedAssetPlugin {
                         "plugin EmbeddedAssetPlugin must be added before plugin AssetPlugin when replacing the default asset source"
                     );
                 }
+                let setup = ReaderSetup::new(app);
+                let processed_setup = setup.clone();
                 app.register_asset_source(
                     AssetSourceId::Default,
-                    AssetSourceBuilder::new(|| Box::new(EmbeddedAssetReader::preloaded()))
-                        .with_processed_reader(|| Box::new(EmbeddedAssetReader::preloaded())),
+                    AssetSourceBuilder::new(move || {
+                        Box::new(setup.apply(EmbeddedAssetReader::preloaded()))
+                    })
+                    .with_processed_reader(move || {
+                        Box::new(processed_setup.apply(EmbeddedAssetReader::preloaded_processed()))
+                    }),
                 );
             }
             #[cfg(feature = "default-source")]
             PluginMode::ReplaceAndFallback { path } => {
+                replace_and_fallback(app, path, DEFAULT_PROCESSED_FILE_PATH);
+            }
+            #[cfg(feature = "default-source")]
+            PluginMode::ReplaceAndFallbackWithProcessed {
+                path,
+                processed_path,
+            } => {
+                replace_and_fallback(app, path, processed_path);
+            }
+            #[cfg(feature = "pack")]
+            PluginMode::Pack { .. } | PluginMode::AppendedPack => {
                 if app.is_plugin_added::<AssetPlugin>() {
                     error!(
                         "plugin EmbeddedAssetPlugin must be added before plugin AssetPlugin when replacing the default asset source"
                     );
                 }
-                let path = path.clone();
+                let pack = self
+                    .open_pack()
+                    .unwrap_or_else(|err| panic!("could not open asset pack: {err}"));
+                let processed_pack = pack.clone();
+                let setup = ReaderSetup::new(app);
+                let processed_setup = setup.clone();
                 app.register_asset_source(
                     AssetSourceId::Default,
-                    AssetSourceBuilder::new(move || {
-                        Box::new(EmbeddedAssetReader::preloaded_with_default(
-                            AssetSource::get_default_reader(path.clone()),
-                        ))
-                    }),
+                    AssetSourceBuilder::new(move || Box::new(setup.apply(pack.reader())))
+                        .with_processed_reader(move || {
+                            Box::new(processed_setup.apply(processed_pack.processed_reader()))
+                        }),
                 );
             }
         }
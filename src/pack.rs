@@ -0,0 +1,293 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    time::SystemTime,
+};
+
+use memmap2::Mmap;
+
+use crate::EmbeddedAssetReader;
+
+include!(concat!(env!("OUT_DIR"), "/pack.rs"));
+
+/// Magic bytes at the start of a pack file, must match `build/pack.rs`.
+const PACK_MAGIC: &[u8; 8] = b"BEVYPACK";
+/// Version of the pack format, must match `build/pack.rs`.
+const PACK_VERSION: u32 = 2;
+
+/// Magic bytes at the end of an executable with a pack appended.
+const FOOTER_MAGIC: &[u8; 8] = b"BEVYPEND";
+/// Length of the footer after an appended pack: the length of the pack, and the magic bytes.
+const FOOTER_LEN: usize = 8 + FOOTER_MAGIC.len();
+/// Alignment of an appended pack in the executable, so that the alignment of the assets in the
+/// pack holds once mapped.
+const APPENDED_ALIGN: usize = 4096;
+
+/// A file by its path, length and modification time, to know if it changed.
+type FileVersion = (PathBuf, u64, Option<SystemTime>);
+
+/// Packs already opened, to map each file only once.
+static OPENED: Mutex<BTreeMap<FileVersion, &'static Mmap>> = Mutex::new(BTreeMap::new());
+
+/// An asset in an [`AssetPack`].
+#[derive(Debug, Clone, Copy)]
+pub struct PackedAsset<'a> {
+    /// Path of the asset, relative to the asset folder.
+    pub path: &'a str,
+    /// If this is a processed asset, or a raw one.
+    pub processed: bool,
+    /// Content of the asset.
+    pub data: &'a [u8],
+    /// Alignment in bytes of the content, as set by `BEVY_EMBEDDED_ASSETS_ALIGN`. This is `1`
+    /// when the content is not aligned.
+    pub align: usize,
+}
+
+/// A pack file written by the build script, with all the assets outside of the executable.
+///
+/// This is only available when the `pack` cargo feature is enabled. The build script then writes
+/// `assets.pack` next to the executable in the `target` folder, or to the path set by the
+/// `BEVY_EMBEDDED_ASSETS_PACK_PATH` environment variable.
+///
+/// The pack is memory-mapped, and stays mapped until the program exits. Assets are read from it
+/// without copying, through [`EmbeddedAssetReader`].
+///
+/// The data of each asset is aligned in the pack as set by `BEVY_EMBEDDED_ASSETS_ALIGN`, like the
+/// assets embedded in the executable.
+///
+/// ```rust,no_run
+/// # use bevy_embedded_assets::AssetPack;
+/// # use std::path::Path;
+/// let pack = AssetPack::open("assets.pack").unwrap();
+/// let reader = pack.reader();
+/// let data: &'static [u8] = reader.load_path_sync(Path::new("image.png")).unwrap().0;
+/// ```
+#[derive(Debug, Clone)]
+pub struct AssetPack {
+    entries: Vec<PackedAsset<'static>>,
+}
+
+impl AssetPack {
+    /// Open and memory-map a pack.
+    ///
+    /// A relative path is looked up next to the executable. In debug builds, it is then looked up
+    /// in the folder where the build script wrote the pack, for tests and examples.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if the pack can't be opened, or is not a valid pack.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_bytes(map(resolve(path.as_ref()))?)
+    }
+
+    /// Open the pack appended to the running executable with [`AssetPack::append`].
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if the executable can't be opened, or doesn't end with a pack.
+    pub fn from_current_exe() -> io::Result<Self> {
+        Self::open_appended(env::current_exe()?)
+    }
+
+    /// Open the pack appended to an executable with [`AssetPack::append`].
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if the executable can't be opened, or doesn't end with a pack.
+    pub fn open_appended(executable: impl AsRef<Path>) -> io::Result<Self> {
+        let executable = map(executable.as_ref().to_path_buf())?;
+        let start = appended_pack_start(executable)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no asset pack appended"))?;
+        Self::from_bytes(&executable[start..executable.len() - FOOTER_LEN])
+    }
+
+    /// Append a pack to an already built executable, so that it can be read with
+    /// [`AssetPack::from_current_exe`] without building it again. A pack previously appended is
+    /// replaced.
+    ///
+    /// The pack starts at a multiple of 4096 bytes in the executable, padded with zeros, to keep
+    /// the alignment of its assets. A footer is added after the pack with its length, to find it
+    /// from the end of the file.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if the pack is not valid, or the files can't be read or written.
+    /// It also fails when replacing a pack appended to the executable that was opened by this
+    /// program, as it stays mapped until it exits.
+    pub fn append(executable: impl AsRef<Path>, pack: impl AsRef<Path>) -> io::Result<()> {
+        let pack = fs::read(pack)?;
+        if parse(&pack).is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a valid asset pack",
+            ));
+        }
+        let executable = executable.as_ref();
+        let mut file = OpenOptions::new().read(true).write(true).open(executable)?;
+        let len = file.metadata()?.len();
+        let mut end = len;
+        if let Some(footer) = len.checked_sub(FOOTER_LEN as u64) {
+            let mut bytes = [0; FOOTER_LEN];
+            file.seek(SeekFrom::Start(footer))?;
+            file.read_exact(&mut bytes)?;
+            if let Some(pack_len) = appended_pack_len(&bytes) {
+                end = footer.checked_sub(pack_len).unwrap_or(len);
+            }
+        }
+        let end = end.next_multiple_of(APPENDED_ALIGN as u64);
+        if mapped_len(executable).is_some_and(|mapped| end < mapped) {
+            // truncating a mapped file would crash when reading the removed part
+            return Err(io::Error::new(
+                io::ErrorKind::ResourceBusy,
+                "the asset pack appended to this executable is in use",
+            ));
+        }
+        file.set_len(end)?;
+        file.seek(SeekFrom::End(0))?;
+        file.write_all(&pack)?;
+        file.write_all(&(pack.len() as u64).to_le_bytes())?;
+        file.write_all(FOOTER_MAGIC)
+    }
+
+    fn from_bytes(pack: &'static [u8]) -> io::Result<Self> {
+        Ok(Self {
+            entries: parse(pack).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "not a valid asset pack")
+            })?,
+        })
+    }
+
+    /// All raw and processed assets in the pack, in the order they were packed.
+    #[must_use]
+    pub fn assets(&self) -> &[PackedAsset<'static>] {
+        &self.entries
+    }
+
+    /// Paths of all raw and processed assets in the pack.
+    pub fn paths(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.entries.iter().map(|entry| entry.path)
+    }
+
+    /// Create an [`EmbeddedAssetReader`] with the raw assets of the pack.
+    #[must_use]
+    pub fn reader(&self) -> EmbeddedAssetReader {
+        self.reader_for(false)
+    }
+
+    /// Create an [`EmbeddedAssetReader`] with the processed assets of the pack.
+    #[must_use]
+    pub fn processed_reader(&self) -> EmbeddedAssetReader {
+        self.reader_for(true)
+    }
+
+    fn reader_for(&self, processed: bool) -> EmbeddedAssetReader {
+        let mut reader = EmbeddedAssetReader::default();
+        for entry in self.entries.iter().filter(|e| e.processed == processed) {
+            reader.add_asset(Path::new(entry.path), entry.data);
+        }
+        reader
+    }
+}
+
+/// Memory-map a file, or get it if it was already mapped and has not changed since.
+fn map(path: PathBuf) -> io::Result<&'static [u8]> {
+    let file = File::open(&path)?;
+    let metadata = file.metadata()?;
+    let key = (path, metadata.len(), metadata.modified().ok());
+    let mut opened = OPENED.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(map) = opened.get(&key) {
+        return Ok(map);
+    }
+    // SAFETY: the pack is not expected to be modified while the game is running. If it is,
+    // reading an asset may return inconsistent data.
+    #[allow(unsafe_code)]
+    let map = unsafe { Mmap::map(&file)? };
+    let map = Box::leak(Box::new(map));
+    opened.insert(key, map);
+    Ok(map)
+}
+
+/// Length of the largest mapping of a file, which may still be read.
+fn mapped_len(path: &Path) -> Option<u64> {
+    let path = path.canonicalize().ok()?;
+    OPENED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .keys()
+        .filter(|(opened, _, _)| opened.canonicalize().is_ok_and(|opened| opened == path))
+        .map(|(_, len, _)| *len)
+        .max()
+}
+
+/// Find where the pack appended to an executable starts, from its footer.
+fn appended_pack_start(executable: &[u8]) -> Option<usize> {
+    let footer = executable.len().checked_sub(FOOTER_LEN)?;
+    let len = appended_pack_len(&executable[footer..])?;
+    footer.checked_sub(usize::try_from(len).ok()?)
+}
+
+/// Length of the pack appended before a footer, if it is one.
+fn appended_pack_len(footer: &[u8]) -> Option<u64> {
+    if &footer[8..] != FOOTER_MAGIC {
+        return None;
+    }
+    Some(u64::from_le_bytes(footer[..8].try_into().ok()?))
+}
+
+fn resolve(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(path)))
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| {
+            #[cfg(debug_assertions)]
+            let path = Path::new(PACK_BUILD_DIR).join(path);
+            #[cfg(not(debug_assertions))]
+            let path = path.to_path_buf();
+            path
+        })
+}
+
+/// Parse the index of a pack.
+fn parse<'a>(pack: &'a [u8]) -> Option<Vec<PackedAsset<'a>>> {
+    let mut cursor = pack;
+    let mut take = |len: usize| -> Option<&'a [u8]> {
+        let (taken, rest) = cursor.split_at_checked(len)?;
+        cursor = rest;
+        Some(taken)
+    };
+    if take(PACK_MAGIC.len())? != PACK_MAGIC
+        || u32::from_le_bytes(take(4)?.try_into().ok()?) != PACK_VERSION
+    {
+        return None;
+    }
+    let count = u32::from_le_bytes(take(4)?.try_into().ok()?);
+    // each entry of the index takes at least 25 bytes, don't trust the count of a corrupted pack
+    let mut entries = Vec::with_capacity((count as usize).min(pack.len() / 25));
+    for _ in 0..count {
+        let processed = take(1)?[0] == 1;
+        let len = u32::from_le_bytes(take(4)?.try_into().ok()?) as usize;
+        let path = std::str::from_utf8(take(len)?).ok()?;
+        let offset = usize::try_from(u64::from_le_bytes(take(8)?.try_into().ok()?)).ok()?;
+        let len = usize::try_from(u64::from_le_bytes(take(8)?.try_into().ok()?)).ok()?;
+        let align = u32::from_le_bytes(take(4)?.try_into().ok()?) as usize;
+        let data = pack.get(offset..offset.checked_add(len)?)?;
+        if !align.is_power_of_two() || !offset.is_multiple_of(align) {
+            return None;
+        }
+        entries.push(PackedAsset {
+            path,
+            processed,
+            data,
+            align,
+        });
+    }
+    Some(entries)
+}
@@ -0,0 +1,79 @@
+use std::{
+    collections::BTreeSet,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use bevy_app::AppExit;
+use bevy_ecs::{message::MessageReader, resource::Resource, system::Res};
+use log::error;
+
+/// Environment variable with the file where to write the recorded paths on exit.
+const RECORD_PATH_VAR: &str = "BEVY_EMBEDDED_ASSETS_RECORD";
+/// File where to write the recorded paths on exit when not set.
+const DEFAULT_RECORD_PATH: &str = "embedded_assets_access.txt";
+
+/// Records the paths of embedded assets that are read, to find the ones that are never used.
+///
+/// This is only available when the `record-access` cargo feature is enabled. When replacing the
+/// default asset source, the [`EmbeddedAssetPlugin`](crate::EmbeddedAssetPlugin) inserts it as a
+/// resource and writes the recorded paths on exit to the file set by the
+/// `BEVY_EMBEDDED_ASSETS_RECORD` environment variable, or `embedded_assets_access.txt`. The
+/// build script can then use those files to report or exclude unused assets.
+#[derive(Resource, Clone, Default, Debug)]
+pub struct AccessRecorder(Arc<Mutex<BTreeSet<PathBuf>>>);
+
+impl AccessRecorder {
+    fn lock(&self) -> MutexGuard<'_, BTreeSet<PathBuf>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Record that an embedded asset was read.
+    pub(crate) fn record(&self, path: &Path) {
+        self.lock().insert(path.to_path_buf());
+    }
+
+    /// Paths of the embedded assets read so far, sorted.
+    #[must_use]
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// Write the recorded paths to a file, one per line, merged with the paths already in the
+    /// file if it exists.
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if the file can't be read or written.
+    pub fn write(&self, file: &Path) -> io::Result<()> {
+        let mut paths = match fs::read_to_string(file) {
+            Ok(existing) => existing
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(PathBuf::from)
+                .collect(),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => BTreeSet::new(),
+            Err(err) => return Err(err),
+        };
+        paths.extend(self.lock().iter().cloned());
+        let mut content = String::new();
+        for path in paths {
+            content.push_str(&path.to_string_lossy());
+            content.push('\n');
+        }
+        fs::write(file, content)
+    }
+}
+
+/// Write the recorded paths when the app exits.
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn write_on_exit(mut exit: MessageReader<AppExit>, recorder: Res<AccessRecorder>) {
+    if exit.read().next().is_none() {
+        return;
+    }
+    let file = std::env::var(RECORD_PATH_VAR).unwrap_or_else(|_| DEFAULT_RECORD_PATH.to_string());
+    if let Err(err) = recorder.write(Path::new(&file)) {
+        error!("could not write embedded assets access record to {file}: {err}");
+    }
+}
@@ -0,0 +1,34 @@
+use std::{env, path::PathBuf};
+
+include!(concat!(env!("OUT_DIR"), "/sidecar.rs"));
+
+/// Environment variable to override the sidecar folder at runtime.
+const SIDECAR_PATH_VAR: &str = "BEVY_EMBEDDED_ASSETS_SIDECAR_PATH";
+
+/// Find the folder with the assets too large to be embedded.
+///
+/// It is the folder set by the `BEVY_EMBEDDED_ASSETS_SIDECAR_PATH` environment variable if set, or
+/// the sidecar folder next to the executable. In debug builds, it can also be the one created by
+/// the build when the executable was moved without it, like for tests and examples.
+pub(crate) fn dir() -> PathBuf {
+    if let Ok(dir) = env::var(SIDECAR_PATH_VAR) {
+        return PathBuf::from(dir);
+    }
+    env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(SIDECAR_DIR)))
+        .filter(|dir| dir.exists())
+        .or_else(|| {
+            #[cfg(debug_assertions)]
+            let build_dir = Some(PathBuf::from(SIDECAR_BUILD_DIR)).filter(|dir| dir.exists());
+            #[cfg(not(debug_assertions))]
+            let build_dir = None;
+            build_dir
+        })
+        .unwrap_or_else(|| PathBuf::from(SIDECAR_DIR))
+}
+
+/// Folder with the raw or the processed sidecar assets.
+pub(crate) fn source_dir(processed: bool) -> PathBuf {
+    dir().join(if processed { "processed" } else { "raw" })
+}
@@ -83,3 +83,44 @@ fn work_with_embedded_source_plugin_before() {
     let asset = test_assets.get(&handle_4).unwrap();
     assert_eq!(asset.value, "at runtime");
 }
+
+#[cfg(feature = "diagnostics")]
+#[test]
+fn count_embedded_and_fallback_reads() {
+    use bevy::diagnostic::DiagnosticsStore;
+    use bevy_embedded_assets::EmbeddedAssetStats;
+
+    let mut app = App::new();
+    app.add_plugins(EmbeddedAssetPlugin {
+        mode: PluginMode::ReplaceAndFallback {
+            path: "runtime_assets".to_string(),
+        },
+    })
+    .add_plugins(DefaultPlugins.set(AssetPlugin {
+        file_path: "runtime_assets".to_string(),
+        ..default()
+    }))
+    .init_asset::<TestAsset>()
+    .init_asset_loader::<TestAssetLoader>();
+    app.finish();
+
+    let asset_server = app.world_mut().resource_mut::<AssetServer>();
+    let embedded: Handle<TestAsset> = asset_server.load("subdir/other_asset.test");
+    let fallback: Handle<TestAsset> = asset_server.load("asset.test");
+    app.update();
+    let test_assets = app.world().resource::<Assets<TestAsset>>();
+    assert!(test_assets.get(&embedded).is_some());
+    assert!(test_assets.get(&fallback).is_some());
+
+    let stats = app.world().resource::<EmbeddedAssetStats>().snapshot();
+    assert_eq!(stats.reads, 2);
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.fallback_hits, 1);
+    assert_eq!(stats.embedded_bytes, "in subdirectory".len() as u64);
+    assert_eq!(stats.fallback_bytes, "at runtime".len() as u64);
+
+    app.update();
+    let diagnostics = app.world().resource::<DiagnosticsStore>();
+    let reads = diagnostics.get(&EmbeddedAssetStats::READS).unwrap();
+    assert_eq!(reads.value(), Some(2.0));
+}
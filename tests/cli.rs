@@ -0,0 +1,73 @@
+#![cfg(feature = "cli")]
+
+use std::{path::PathBuf, process::Command};
+
+fn pack() -> PathBuf {
+    std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("assets.pack")
+}
+
+fn run(args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_bevy_embedded_assets"))
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn list() {
+    let (success, output) = run(&["list", pack().to_str().unwrap()]);
+    assert!(success);
+    assert!(output.contains("subdir/other_asset.test"));
+    assert!(output.contains("example_asset.test (processed)"));
+    assert!(output.contains(&blake3::hash(b"hello").to_hex().to_string()));
+}
+
+#[test]
+fn extract_and_verify() {
+    let dir = std::env::temp_dir().join("bevy_embedded_assets_cli_extract");
+    let _ = std::fs::remove_dir_all(&dir);
+    let pack = pack();
+    let pack = pack.to_str().unwrap();
+    let (success, _) = run(&["extract", pack, dir.to_str().unwrap()]);
+    assert!(success);
+    assert_eq!(
+        std::fs::read(dir.join("assets/subdir/other_asset.test")).unwrap(),
+        b"in subdirectory"
+    );
+
+    let assets = dir.join("assets");
+    let processed = dir.join("imported_assets/Default");
+    let (success, _) = run(&[
+        "verify",
+        pack,
+        assets.to_str().unwrap(),
+        processed.to_str().unwrap(),
+    ]);
+    assert!(success);
+
+    std::fs::write(assets.join("example_asset.test"), "changed").unwrap();
+    std::fs::write(assets.join("new.test"), "new").unwrap();
+    let (success, output) = run(&["verify", pack, assets.to_str().unwrap()]);
+    assert!(!success);
+    assert!(output.contains("different: example_asset.test"));
+    assert!(output.contains("not in pack: new.test"));
+}
+
+#[test]
+fn diff() {
+    let pack = pack();
+    let pack = pack.to_str().unwrap();
+    let (success, output) = run(&["diff", pack, pack]);
+    assert!(success);
+    assert!(output.is_empty());
+}
@@ -1,7 +1,9 @@
+#![cfg(feature = "bevy")]
+
 use std::fmt::Display;
 
 use bevy::{
-    asset::{LoadContext, io::Reader},
+    asset::{AssetPath, AssetServerMode, LoadContext, io::Reader},
     prelude::*,
 };
 use bevy_embedded_assets::EmbeddedAssetPlugin;
@@ -117,3 +119,103 @@ fn doesnt_work_with_plugin() {
     let asset = test_assets.get(&handle_3).unwrap();
     assert_eq!(asset.value, "in subdirectory");
 }
+
+#[derive(Asset, TypePath, Debug)]
+pub struct StaticAsset {
+    pub bytes: &'static [u8],
+}
+
+#[derive(TypePath)]
+pub struct StaticAssetLoader {
+    mode: AssetServerMode,
+}
+
+impl FromWorld for StaticAssetLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            mode: world.resource::<AssetServer>().mode(),
+        }
+    }
+}
+
+impl bevy::asset::AssetLoader for StaticAssetLoader {
+    type Asset = StaticAsset;
+    type Settings = ();
+    type Error = TestError;
+    async fn load(
+        &self,
+        _: &mut dyn Reader,
+        _: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let bytes = bevy_embedded_assets::embedded_bytes(load_context.path(), self.mode)
+            .ok_or(TestError)?;
+        Ok(StaticAsset { bytes })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["test"]
+    }
+}
+
+#[test]
+fn get_embedded_bytes() {
+    let data = bevy_embedded_assets::embedded::get("subdir/other_asset.test")
+        .and_then(|asset| asset.data())
+        .unwrap();
+    for path in [
+        "subdir/other_asset.test",
+        "embedded://subdir/other_asset.test",
+        "subdir/other_asset.test#label",
+        // paths are only normalized when the readers replacing the default source are available
+        #[cfg(feature = "default-source")]
+        "./subdir/other_asset.test",
+    ] {
+        let bytes = bevy_embedded_assets::embedded_bytes(
+            &AssetPath::from(path),
+            AssetServerMode::Unprocessed,
+        )
+        .unwrap();
+        assert_eq!(bytes.as_ptr(), data.as_ptr());
+    }
+    #[cfg(feature = "case-insensitive")]
+    assert_eq!(
+        bevy_embedded_assets::embedded_bytes(
+            &AssetPath::from("Subdir/Other_Asset.test"),
+            AssetServerMode::Unprocessed
+        )
+        .unwrap()
+        .as_ptr(),
+        data.as_ptr()
+    );
+    assert!(
+        bevy_embedded_assets::embedded_bytes(
+            &AssetPath::from("missing.test"),
+            AssetServerMode::Unprocessed
+        )
+        .is_none()
+    );
+    assert!(
+        bevy_embedded_assets::embedded_bytes(
+            &AssetPath::from("other://subdir/other_asset.test"),
+            AssetServerMode::Unprocessed
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn loader_uses_embedded_bytes() {
+    let mut app = App::new();
+    app.add_plugins(EmbeddedAssetPlugin::default())
+        .add_plugins(DefaultPlugins)
+        .init_asset::<StaticAsset>()
+        .init_asset_loader::<StaticAssetLoader>();
+    app.finish();
+
+    let asset_server = app.world_mut().resource_mut::<AssetServer>();
+    let handle: Handle<StaticAsset> = asset_server.load("embedded://example_asset.test");
+    app.update();
+    let static_assets = app.world_mut().resource_mut::<Assets<StaticAsset>>();
+    assert_eq!(static_assets.get(&handle).unwrap().bytes, b"hello");
+}
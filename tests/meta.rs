@@ -0,0 +1,132 @@
+#![cfg(feature = "default-source")]
+
+use std::fmt::Display;
+
+use bevy::{
+    asset::{AssetMetaCheck, AssetPath, LoadContext, io::Reader},
+    prelude::*,
+};
+use bevy_embedded_assets::{EmbeddedAssetPlugin, PluginMode};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Asset, TypePath, Debug)]
+pub struct TestAsset {
+    pub value: String,
+}
+
+#[derive(Default, TypePath)]
+pub struct TestAssetLoader;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct TestSettings {
+    pub suffix: String,
+}
+
+#[derive(Debug, Error)]
+pub struct TestError;
+
+impl Display for TestError {
+    fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Ok(())
+    }
+}
+
+impl bevy::asset::AssetLoader for TestAssetLoader {
+    type Asset = TestAsset;
+    type Settings = TestSettings;
+    type Error = TestError;
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &TestSettings,
+        _: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        bevy::asset::AsyncReadExt::read_to_end(reader, &mut bytes)
+            .await
+            .unwrap();
+
+        Ok(TestAsset {
+            value: String::from_utf8(bytes).unwrap() + &settings.suffix,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["test"]
+    }
+}
+
+#[test]
+fn meta_check_only_lists_embedded_metas() {
+    let AssetMetaCheck::Paths(paths) = (EmbeddedAssetPlugin {
+        mode: PluginMode::ReplaceDefault,
+    })
+    .meta_check() else {
+        panic!("meta check should list paths");
+    };
+    assert!(paths.contains(&AssetPath::from("with_meta.test")));
+    assert!(!paths.contains(&AssetPath::from("example_asset.test")));
+
+    let AssetMetaCheck::Paths(paths) = EmbeddedAssetPlugin::default().meta_check() else {
+        panic!("meta check should list paths");
+    };
+    assert!(paths.contains(&AssetPath::from("embedded://with_meta.test")));
+}
+
+#[test]
+fn embedded_meta_with_meta_check() {
+    let embedded = EmbeddedAssetPlugin {
+        mode: PluginMode::ReplaceDefault,
+    };
+    let mut app = App::new();
+    app.add_plugins(embedded.clone())
+        .add_plugins(DefaultPlugins.set(AssetPlugin {
+            file_path: "test".to_string(),
+            meta_check: embedded.meta_check(),
+            ..default()
+        }))
+        .init_asset::<TestAsset>()
+        .init_asset_loader::<TestAssetLoader>();
+    app.finish();
+
+    let asset_server = app.world_mut().resource_mut::<AssetServer>();
+    let handle_1: Handle<TestAsset> = asset_server.load("with_meta.test");
+    let handle_2: Handle<TestAsset> = asset_server.load("example_asset.test");
+    app.update();
+    let test_assets = app.world_mut().resource_mut::<Assets<TestAsset>>();
+    let asset = test_assets.get(&handle_1).unwrap();
+    assert_eq!(asset.value, "with meta from embedded meta");
+    let asset = test_assets.get(&handle_2).unwrap();
+    assert_eq!(asset.value, "hello");
+}
+
+#[test]
+fn metas_are_not_mixed_with_fallback() {
+    let mut app = App::new();
+    app.add_plugins(EmbeddedAssetPlugin {
+        mode: PluginMode::ReplaceAndFallback {
+            path: "runtime_assets".to_string(),
+        },
+    })
+    .add_plugins(DefaultPlugins.set(AssetPlugin {
+        file_path: "runtime_assets".to_string(),
+        ..default()
+    }))
+    .init_asset::<TestAsset>()
+    .init_asset_loader::<TestAssetLoader>();
+    app.finish();
+
+    let asset_server = app.world_mut().resource_mut::<AssetServer>();
+    let handle_1: Handle<TestAsset> = asset_server.load("with_meta.test");
+    let handle_2: Handle<TestAsset> = asset_server.load("example_asset.test");
+    let handle_3: Handle<TestAsset> = asset_server.load("with_runtime_meta.test");
+    app.update();
+    let test_assets = app.world_mut().resource_mut::<Assets<TestAsset>>();
+    let asset = test_assets.get(&handle_1).unwrap();
+    assert_eq!(asset.value, "with meta from embedded meta");
+    let asset = test_assets.get(&handle_2).unwrap();
+    assert_eq!(asset.value, "hello");
+    let asset = test_assets.get(&handle_3).unwrap();
+    assert_eq!(asset.value, "at runtime from fallback meta");
+}
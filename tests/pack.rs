@@ -0,0 +1,170 @@
+#![cfg(feature = "pack")]
+
+use std::{fmt::Display, path::Path};
+
+use bevy::{
+    asset::{LoadContext, io::Reader},
+    prelude::*,
+};
+use bevy_embedded_assets::{AssetPack, EmbeddedAssetPlugin, PluginMode};
+use thiserror::Error;
+
+#[derive(Asset, TypePath, Debug)]
+pub struct TestAsset {
+    pub value: String,
+}
+
+#[derive(Default, TypePath)]
+pub struct TestAssetLoader;
+
+#[derive(Debug, Error)]
+pub struct TestError;
+
+impl Display for TestError {
+    fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Ok(())
+    }
+}
+
+impl bevy::asset::AssetLoader for TestAssetLoader {
+    type Asset = TestAsset;
+    type Settings = ();
+    type Error = TestError;
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &(),
+        _: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        bevy::asset::AsyncReadExt::read_to_end(reader, &mut bytes)
+            .await
+            .unwrap();
+
+        Ok(TestAsset {
+            value: String::from_utf8(bytes).unwrap(),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["test"]
+    }
+}
+
+#[test]
+fn load_assets_from_pack() {
+    let mut app = App::new();
+    app.add_plugins(EmbeddedAssetPlugin {
+        mode: PluginMode::Pack {
+            path: "assets.pack".to_string(),
+        },
+    })
+    .add_plugins(DefaultPlugins.set(AssetPlugin {
+        file_path: "test".to_string(),
+        ..default()
+    }))
+    .init_asset::<TestAsset>()
+    .init_asset_loader::<TestAssetLoader>();
+    app.finish();
+
+    let asset_server = app.world_mut().resource_mut::<AssetServer>();
+    let handle_1: Handle<TestAsset> = asset_server.load("example_asset.test");
+    let handle_2: Handle<TestAsset> = asset_server.load("subdir/other_asset.test");
+    app.update();
+    let test_assets = app.world_mut().resource_mut::<Assets<TestAsset>>();
+    let asset = test_assets.get(&handle_1).unwrap();
+    assert_eq!(asset.value, "hello");
+    let asset = test_assets.get(&handle_2).unwrap();
+    assert_eq!(asset.value, "in subdirectory");
+}
+
+#[test]
+fn read_pack() {
+    let pack = AssetPack::open("assets.pack").unwrap();
+    assert!(
+        pack.paths()
+            .any(|path| path == "subdir/duplicate_asset.test")
+    );
+    let reader = pack.reader();
+    let data = reader
+        .load_path_sync(Path::new("example_asset.test"))
+        .unwrap();
+    let duplicate = reader
+        .load_path_sync(Path::new("subdir/duplicate_asset.test"))
+        .unwrap();
+    // identical assets share their data in the pack
+    assert_eq!(data.0.as_ptr(), duplicate.0.as_ptr());
+    let asset = pack
+        .assets()
+        .iter()
+        .find(|asset| asset.path == "example_asset.test")
+        .unwrap();
+    assert_eq!(asset.align, 16);
+    assert_eq!(asset.data.as_ptr() as usize % 16, 0);
+    let processed = pack.processed_reader();
+    assert_eq!(
+        processed
+            .load_path_sync(Path::new("example_asset.test"))
+            .unwrap()
+            .0,
+        b"hello processed"
+    );
+}
+
+#[test]
+fn read_pack_appended_to_executable() {
+    let dir = std::env::temp_dir().join("bevy_embedded_assets_appended_pack");
+    std::fs::create_dir_all(&dir).unwrap();
+    let executable = dir.join("game.exe");
+    std::fs::write(&executable, b"not really an executable").unwrap();
+    let pack = AssetPack::open("assets.pack").unwrap();
+    let pack_path = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("assets.pack");
+    assert!(AssetPack::open_appended(&executable).is_err());
+
+    AssetPack::append(&executable, &pack_path).unwrap();
+    let size = std::fs::metadata(&executable).unwrap().len();
+    // appending again replaces the previous pack
+    AssetPack::append(&executable, &pack_path).unwrap();
+    assert_eq!(std::fs::metadata(&executable).unwrap().len(), size);
+    assert!(
+        std::fs::read(&executable)
+            .unwrap()
+            .starts_with(b"not really an executable")
+    );
+
+    let appended = AssetPack::open_appended(&executable).unwrap();
+    assert_eq!(
+        appended.paths().collect::<Vec<_>>(),
+        pack.paths().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        appended
+            .reader()
+            .load_path_sync(Path::new("subdir/other_asset.test"))
+            .unwrap()
+            .0,
+        b"in subdirectory"
+    );
+    // the appended pack is mapped, it can't be replaced
+    assert!(AssetPack::append(&executable, &pack_path).is_err());
+}
+
+#[test]
+fn reject_corrupted_pack() {
+    let dir = std::env::temp_dir().join("bevy_embedded_assets_corrupted_pack");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("corrupted.pack");
+    let mut pack = b"BEVYPACK".to_vec();
+    pack.extend_from_slice(&2_u32.to_le_bytes());
+    pack.extend_from_slice(&u32::MAX.to_le_bytes());
+    pack.extend_from_slice(&[0; 8]);
+    std::fs::write(&path, pack).unwrap();
+
+    assert!(AssetPack::open(&path).is_err());
+}
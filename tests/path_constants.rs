@@ -0,0 +1,21 @@
+#![cfg(feature = "path-constants")]
+
+use bevy_embedded_assets::{assets, embedded};
+
+#[test]
+fn constants_match_paths() {
+    assert_eq!(assets::EXAMPLE_ASSET_TEST, "example_asset.test");
+    assert_eq!(assets::AÇÈT_TEST, "açèt.test");
+    assert_eq!(assets::subdir::OTHER_ASSET_TEST, "subdir/other_asset.test");
+}
+
+#[test]
+fn constants_are_embedded() {
+    for path in [
+        assets::EXAMPLE_ASSET_TEST,
+        assets::subdir::OTHER_ASSET_TEST,
+        assets::subdir::DUPLICATE_ASSET_TEST,
+    ] {
+        assert!(embedded::get(path).is_some());
+    }
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "default-source")]
+
+use std::fmt::Display;
+
+use bevy::{
+    asset::{AssetPath, LoadContext, io::Reader},
+    prelude::*,
+};
+use bevy_embedded_assets::{EmbeddedAssetPlugin, PluginMode};
+use thiserror::Error;
+
+#[derive(Asset, TypePath, Debug)]
+pub struct TestAsset {
+    pub value: String,
+}
+
+#[derive(Default, TypePath)]
+pub struct TestAssetLoader;
+
+#[derive(Debug, Error)]
+pub struct TestError;
+
+impl Display for TestError {
+    fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Ok(())
+    }
+}
+
+impl bevy::asset::AssetLoader for TestAssetLoader {
+    type Asset = TestAsset;
+    type Settings = ();
+    type Error = TestError;
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &(),
+        _: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        bevy::asset::AsyncReadExt::read_to_end(reader, &mut bytes)
+            .await
+            .unwrap();
+
+        Ok(TestAsset {
+            value: String::from_utf8(bytes).unwrap(),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["test"]
+    }
+}
+
+#[test]
+fn load_processed_assets() {
+    let mut app = App::new();
+    app.add_plugins(EmbeddedAssetPlugin {
+        mode: PluginMode::ReplaceDefault,
+    })
+    .add_plugins(DefaultPlugins.set(AssetPlugin {
+        file_path: "test".to_string(),
+        mode: AssetMode::Processed,
+        ..default()
+    }))
+    .init_asset::<TestAsset>()
+    .init_asset_loader::<TestAssetLoader>();
+    app.finish();
+
+    let asset_server = app.world_mut().resource_mut::<AssetServer>();
+    let handle_1: Handle<TestAsset> = asset_server.load("example_asset.test");
+    let handle_2: Handle<TestAsset> = asset_server.load("açèt.test");
+    app.update();
+    let test_assets = app.world_mut().resource_mut::<Assets<TestAsset>>();
+    let asset = test_assets.get(&handle_1).unwrap();
+    assert_eq!(asset.value, "hello processed");
+    assert!(test_assets.get(&handle_2).is_none());
+
+    // loaders get the data of the processed asset too
+    let mode = app.world().resource::<AssetServer>().mode();
+    assert_eq!(
+        bevy_embedded_assets::embedded_bytes(&AssetPath::from("./example_asset.test"), mode),
+        Some(&b"hello processed"[..])
+    );
+    assert_eq!(
+        bevy_embedded_assets::embedded_bytes(
+            &AssetPath::from("embedded://example_asset.test"),
+            mode
+        ),
+        Some(&b"hello"[..])
+    );
+}
+
+#[test]
+fn load_raw_assets_when_processed_are_embedded() {
+    let mut app = App::new();
+    app.add_plugins(EmbeddedAssetPlugin {
+        mode: PluginMode::ReplaceDefault,
+    })
+    .add_plugins(DefaultPlugins.set(AssetPlugin {
+        file_path: "test".to_string(),
+        ..default()
+    }))
+    .init_asset::<TestAsset>()
+    .init_asset_loader::<TestAssetLoader>();
+    app.finish();
+
+    let asset_server = app.world_mut().resource_mut::<AssetServer>();
+    let handle: Handle<TestAsset> = asset_server.load("example_asset.test");
+    app.update();
+    let test_assets = app.world_mut().resource_mut::<Assets<TestAsset>>();
+    assert_eq!(test_assets.get(&handle).unwrap().value, "hello");
+
+    // loaders get the same data as the asset server
+    let mode = app.world().resource::<AssetServer>().mode();
+    assert_eq!(
+        bevy_embedded_assets::embedded_bytes(&AssetPath::from("example_asset.test"), mode),
+        Some(&b"hello"[..])
+    );
+}
+
+#[test]
+fn load_processed_assets_with_fallback() {
+    let mut app = App::new();
+    app.add_plugins(EmbeddedAssetPlugin {
+        mode: PluginMode::ReplaceAndFallbackWithProcessed {
+            path: "assets".to_string(),
+            processed_path: "runtime_assets".to_string(),
+        },
+    })
+    .add_plugins(DefaultPlugins.set(AssetPlugin {
+        file_path: "assets".to_string(),
+        processed_file_path: "runtime_assets".to_string(),
+        mode: AssetMode::Processed,
+        ..default()
+    }))
+    .init_asset::<TestAsset>()
+    .init_asset_loader::<TestAssetLoader>();
+    app.finish();
+
+    let asset_server = app.world_mut().resource_mut::<AssetServer>();
+    let handle_1: Handle<TestAsset> = asset_server.load("example_asset.test");
+    let handle_2: Handle<TestAsset> = asset_server.load("asset.test");
+    app.update();
+    let test_assets = app.world_mut().resource_mut::<Assets<TestAsset>>();
+    let asset = test_assets.get(&handle_1).unwrap();
+    assert_eq!(asset.value, "hello processed");
+    let asset = test_assets.get(&handle_2).unwrap();
+    assert_eq!(asset.value, "at runtime");
+}
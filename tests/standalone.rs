@@ -0,0 +1,57 @@
+use bevy_embedded_assets::embedded;
+
+#[test]
+fn get_asset() {
+    let asset = embedded::get("subdir/other_asset.test").unwrap();
+    assert_eq!(asset.path(), "subdir/other_asset.test");
+    assert!(!asset.is_processed());
+    assert_eq!(asset.read().unwrap().as_ref(), b"in subdirectory");
+    assert_eq!(asset.hash().len(), 64);
+
+    assert!(embedded::get("missing.test").is_none());
+    assert!(embedded::get("subdir").is_none());
+}
+
+#[test]
+fn identical_assets_have_the_same_hash() {
+    let asset = embedded::get("example_asset.test").unwrap();
+    let duplicate = embedded::get("subdir/duplicate_asset.test").unwrap();
+    assert_eq!(asset.hash(), duplicate.hash());
+}
+
+#[test]
+fn get_processed_asset() {
+    let asset = embedded::get_processed("example_asset.test").unwrap();
+    assert!(asset.is_processed());
+    assert_eq!(asset.read().unwrap().as_ref(), b"hello processed");
+    assert!(embedded::get_processed("subdir/other_asset.test").is_none());
+}
+
+#[test]
+fn list_assets() {
+    let in_subdir = embedded::read_dir("subdir")
+        .map(embedded::EmbeddedAsset::path)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        in_subdir,
+        ["subdir/duplicate_asset.test", "subdir/other_asset.test"]
+    );
+    assert_eq!(embedded::read_dir("").count(), embedded::raw_assets().len());
+    assert_eq!(
+        embedded::assets().len(),
+        embedded::raw_assets().len() + embedded::processed_assets().len()
+    );
+}
+
+#[test]
+fn data_is_aligned() {
+    for asset in embedded::assets() {
+        assert!(asset.align().is_power_of_two());
+        if let Some(data) = asset.data() {
+            assert_eq!(data.as_ptr() as usize % asset.align(), 0);
+        }
+    }
+    // aligned by the `BEVY_EMBEDDED_ASSETS_ALIGN` set in `.cargo/config.toml`
+    assert_eq!(embedded::get("example_asset.test").unwrap().align(), 16);
+    assert_eq!(embedded::get("with_meta.test.meta").unwrap().align(), 1);
+}